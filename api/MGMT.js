@@ -7,13 +7,43 @@ export const schema = loadSchemas(import.meta.url, {
   handleMsg:   './mgmt/handle.json'
 })
 
+const decoder = new TextDecoder()
+const decode = buffer => decoder.decode(buffer).trim()
+
 export default class MGMT extends SecretNetwork.Contract.withSchema(schema) {
 
   /** query contract status */
   get status () { return this.q.status() }
 
-  /** query current schedule */
-  get schedule () { return this.q.get_schedule() }
+  /** query current schedule (requires the admin's viewing key),
+    * or only the accounts of `address` (requires its viewing key or the admin's) */
+  getSchedule = (key, address = null) =>
+    this.q.schedule({ key, address })
+
+  /** query a page of the claim history, from the `start`-th entry
+    * (requires the admin's viewing key) */
+  getHistory = (key, start = 0, limit = 30) =>
+    this.q.history({ key, start, limit })
+
+  /** query a page of the claim log, from the `start`-th entry
+    * (requires the admin's viewing key) */
+  getClaims = (key, start = 0, limit = 30) =>
+    this.q.claims({ key, start, limit })
+
+  /** query how much someone has claimed so far */
+  claimed = (address, key) =>
+    this.q.claimed({ address, key })
+
+  /** set the viewing key of `agent` (recipients' or admin's) */
+  setViewingKey = (agent, key) =>
+    this.tx.set_viewing_key({ key }, agent)
+
+  /** generate a viewing key for `agent` */
+  async createViewingKey (agent, entropy) {
+    const tx = await this.tx.create_viewing_key({ entropy }, agent)
+    const {key} = JSON.parse(decode(tx.data)).viewing_key
+    return {tx, key}
+  }
 
   /** take over a SNIP20 token */
   acquire = async snip20 => {
@@ -26,25 +56,96 @@ export default class MGMT extends SecretNetwork.Contract.withSchema(schema) {
   configure = schedule =>
     this.tx.configure({ schedule })
 
+  /** load a schedule that is too big for one transaction,
+    * `chunkSize` accounts at a time */
+  async configureInChunks (schedule, chunkSize = 10) {
+    const chunks = accounts => {
+      const result = []
+      for (let i = 0; i < accounts.length; i += chunkSize) {
+        result.push(accounts.slice(i, i + chunkSize))
+      }
+      return result
+    }
+    await this.tx.begin_configure({ total: schedule.total })
+    for (const pool of schedule.pools) {
+      const [first = [], ...rest] = chunks(pool.accounts)
+      await this.tx.append_pool({ pool: { ...pool, accounts: first } })
+      for (const accounts of rest) {
+        await this.tx.append_accounts({ pool_name: pool.name, accounts })
+      }
+    }
+    return this.tx.finalize_configure()
+  }
+
+  /** discard a schedule that is being loaded in chunks */
+  abortConfigure = () =>
+    this.tx.abort_configure()
+
   /** launch the vesting */
   launch = () =>
     this.tx.launch()
 
-  /** claim accumulated portions */
-  claim = claimant =>
-    this.tx.claim({}, claimant)
+  /** claim accumulated portions (or just `amount` of them),
+    * optionally sending them to another address */
+  claim = (claimant, recipient, amount) =>
+    this.tx.claim({ recipient, amount }, claimant)
+
+  /** set where `agent`'s claims are sent by default (`null` to reset) */
+  setClaimDestination = (agent, destination = null) =>
+    this.tx.set_claim_destination({ destination }, agent)
 
   /** see how much is claimable by someone at a certain time */
-  progress = (address, time = + new Date()) => {
+  progress = (address, key, time = + new Date()) => {
     time = Math.floor(time / 1000) // convert JS msec to CosmWasm seconds
     //console.debug(`querying progress for ${address} at ${time} seconds`)
-    return this.q.progress({ address, time })
+    return this.q.progress({ address, time, key })
+  }
+
+  /** see how much is claimable by someone at a certain time, per account */
+  breakdown = (address, key, time = + new Date()) => {
+    time = Math.floor(time / 1000) // convert JS msec to CosmWasm seconds
+    return this.q.breakdown({ address, time, key })
+  }
+
+  /** see when the next portion unlocks for someone, how big it is, and how much is still locked */
+  nextUnlock = (address, key, time = + new Date()) => {
+    time = Math.floor(time / 1000) // convert JS msec to CosmWasm seconds
+    return this.q.next_unlock({ address, time, key })
   }
 
   /** add a new account to a pool */
   add = (pool_name, account) =>
     this.tx.add_account({ pool_name, account })
 
+  /** add several accounts at once, as [pool_name, account] pairs;
+    * if one of them can't be added, none are */
+  addMany = accounts =>
+    this.tx.add_accounts({ accounts })
+
+  /** remove an account that hasn't unlocked anything yet */
+  remove = (pool_name, account_name) =>
+    this.tx.remove_account({ pool_name, account_name })
+
+  /** replace an account that hasn't unlocked anything yet */
+  update = (pool_name, account_name, account) =>
+    this.tx.update_account({ pool_name, account_name, account })
+
+  /** stop vesting an account and return the unvested funds to its pool */
+  revoke = (pool, account) =>
+    this.tx.revoke({ pool, account })
+
+  /** propose to move `agent`'s accounts to `new_address` */
+  proposeAddress = (agent, new_address) =>
+    this.tx.propose_address({ new_address }, agent)
+
+  /** accept the accounts of `address`, as proposed by it */
+  acceptAddress = (agent, address) =>
+    this.tx.accept_address({ address }, agent)
+
+  /** move the accounts of `address` to `new_address` (admin only) */
+  changeAddress = (address, new_address) =>
+    this.tx.change_address({ address, new_address })
+
   /** set the admin */
   setOwner = (new_admin) =>
     this.tx.set_owner({new_admin})
@@ -12,14 +12,43 @@ export default class RPT extends SecretNetwork.Contract.withSchema(schema) {
   /** query contract status */
   get status () { return this.q.status() }
 
-  /** set the splitt proportions */
+  /** query how much has been claimed but not yet distributed */
+  get remainder () { return this.q.remainder() }
+
+  /** query past distributions, oldest first (requires the admin's viewing key) */
+  getHistory = (key, start = 0, limit = 30) =>
+    this.q.history({ key, start, limit })
+
+  /** query how much each recipient has received so far (requires the admin's viewing key) */
+  getTotals = key =>
+    this.q.totals({ key })
+
+  /** set the admin's viewing key for querying the distribution history */
+  setAdminKey = key =>
+    this.tx.set_admin_key({ key })
+
+  /** set the split proportions, and which recipients to notify with a `Send` callback
+    * (`config` is a list of [address, amount] or [address, amount, base64 msg] entries;
+    * recipients without a msg keep the one they had, and an empty msg removes it) */
   configure = (config=[]) =>
-    this.tx.configure({ config })
+    this.tx.configure({ config: config.map(([address, amount, callback])=>[address, { amount, callback }]) })
 
-  /** claim portions from mgmt and distribute them to recipients */
+  /** choose between paying out whole portions ("portions") or splitting everything ("pro_rata") */
+  setMode = (mode="portions") =>
+    this.tx.set_mode({ mode })
+
+  /** let the token know this contract's viewing key (needs to be called once before vesting) */
+  setViewingKey = () =>
+    this.tx.set_viewing_key()
+
+  /** claim from mgmt and distribute what was received to recipients */
   vest = () =>
     this.tx.vest()
 
+  /** distribute what the contract already holds, without claiming from mgmt */
+  distribute = () =>
+    this.tx.distribute()
+
   /** set the admin */
   setOwner = (new_admin) =>
     this.tx.set_owner({new_admin})
@@ -119,6 +119,12 @@ export async function verify ({task, agent, recipients, wallets, contracts, sche
       txs = await Promise.all(txs)
       for (const {tx} of txs) report(tx.transactionHash) })
 
+  // Vesting info is private, so the admin needs a viewing key to monitor it.
+  const MGMT_VK = "admin"
+  await task('set viewing key on MGMT admin', async report => {
+    const {transactionHash} = await MGMT.setViewingKey(agent, MGMT_VK)
+    report(transactionHash) })
+
   // ## And let's go! 🚀
   let launched
   await task('launch the vesting', async report => {
@@ -151,7 +157,6 @@ export async function verify ({task, agent, recipients, wallets, contracts, sche
 
       const claimable = []
 
-      // ⚠️  Vesting info is public!
       await task('query vesting progress', async report => {
         console.info( `ACCOUNT`.padEnd(11)
                     , `CLAIMED`.padEnd(26), `  `
@@ -159,7 +164,7 @@ export async function verify ({task, agent, recipients, wallets, contracts, sche
                     , `TOTAL`.padEnd(26) )
         for (const [name, recipient] of Object.entries(recipients)) {
           if (name.startsWith('TokenPair')) continue // token pairs are only visible to the RPT contract
-          const {progress} = await MGMT.progress(recipient.address, now)
+          const {progress} = await MGMT.progress(recipient.address, MGMT_VK, now)
           const {claimed, unlocked} = progress
           console.info( `${name}`.padEnd(11)
                       , fmtSIENNA(claimed).padStart(26), `of`
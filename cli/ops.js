@@ -1,5 +1,6 @@
 import { stderr } from 'process'
 import { readdirSync, readFileSync, existsSync } from 'fs'
+import { randomBytes } from 'crypto'
 import assert from 'assert'
 
 import bignum from 'bignumber.js'
@@ -169,15 +170,19 @@ export async function initialize (options = {}) {
     initMsg.token   = [TOKEN.address, TOKEN.codeHash]
     initMsg.mgmt    = [MGMT.address,  MGMT.codeHash ]
     initMsg.portion = "2500000000000000000000" // TODO get this from schedule!!!
-    initMsg.config  = [[initialRPTRecipient, initMsg.portion]]
+    initMsg.config  = [[initialRPTRecipient, { amount: initMsg.portion }]]
+    initMsg.entropy = randomBytes(32).toString('base64')
     contracts.RPT = await RPTContract.init({ agent, codeId, label, initMsg })
     report(contracts.RPT.transactionHash) })
+  await task('register rpt viewing key with token', async report => {
+    const {transactionHash} = await contracts.RPT.setViewingKey()
+    report(transactionHash) })
   await task('point rpt account in mgmt schedule to rpt contract', async report => {
     const {MGMT, RPT} = contracts
     schedule.pools.filter(x=>x.name==='MintingPool')[0]
             .accounts.filter(x=>x.name==='RPT')[0]
             .address = RPT.address
-    const {transactionHash} = await MGMT.configure(schedule)
+    const {transactionHash} = await MGMT.configureInChunks(schedule)
     report(transactionHash) })
   console.log(table([
     ['Contract\nDescription',      'Address\nCode hash'],
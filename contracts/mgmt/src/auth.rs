@@ -0,0 +1,51 @@
+//! Viewing keys
+//!
+//! Vesting progress and claim history are private: they can only be queried
+//! by supplying the viewing key of the recipient in question (or of the admin).
+//! Only the SHA-256 hash of each key is stored.
+
+use cosmwasm_std::{Storage, ReadonlyStorage, CanonicalAddr, Env, Binary};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use sha2::{Sha256, Digest};
+
+/// Storage prefix under which key hashes are stored, by canonical address
+pub const PREFIX_VIEWING_KEYS: &[u8] = b"viewing_keys";
+
+/// Generated keys start with this (same as in SNIP20)
+pub const VIEWING_KEY_PREFIX: &str = "api_key_";
+
+/// Generate a new viewing key from user-provided entropy and the environment.
+pub fn create_viewing_key (env: &Env, entropy: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(entropy.as_bytes());
+    hasher.update(env.message.sender.0.as_bytes());
+    hasher.update(&env.block.height.to_be_bytes());
+    hasher.update(&env.block.time.to_be_bytes());
+    format!("{}{}", VIEWING_KEY_PREFIX, Binary::from(hasher.finalize().as_slice()).to_base64())
+}
+
+/// Store the hash of `key` as the viewing key of `address`, replacing the previous one.
+pub fn set_viewing_key <S: Storage> (storage: &mut S, address: &CanonicalAddr, key: &str) {
+    let mut keys = PrefixedStorage::new(PREFIX_VIEWING_KEYS, storage);
+    keys.set(address.as_slice(), &hash(key));
+}
+
+/// Whether `key` is the viewing key of `address`.
+/// Takes the same time whether or not `address` has a key, and whether or not it matches.
+pub fn check_viewing_key <S: ReadonlyStorage> (storage: &S, address: &CanonicalAddr, key: &str) -> bool {
+    let keys = ReadonlyPrefixedStorage::new(PREFIX_VIEWING_KEYS, storage);
+    let hashed = hash(key);
+    match keys.get(address.as_slice()) {
+        Some(expected) => compare(&hashed, &expected),
+        None => { compare(&hashed, &[0u8; 32]); false }
+    }
+}
+
+fn hash (key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+/// Constant-time comparison
+fn compare (a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
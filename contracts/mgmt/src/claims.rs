@@ -0,0 +1,101 @@
+//! Append-only log of claims, and default claim destinations
+//!
+//! `History` only keeps the total claimed by each address; this keeps every claim,
+//! in the order they were made. Entries are stored one per key, so that appending
+//! doesn't require loading the whole log.
+//!
+//! A recipient can also register an address to which their claims are sent by default
+//! (e.g. a cold wallet), instead of to the address that signs the claim.
+
+use cosmwasm_std::{
+    Storage, ReadonlyStorage, Api, StdResult, Uint128, HumanAddr, CanonicalAddr, Binary, to_vec, from_slice
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use sienna_schedule::Seconds;
+
+/// Storage prefix under which entries are stored, by index
+pub const PREFIX_CLAIMS: &[u8] = b"claims";
+
+/// Storage key under which the number of entries is stored
+pub const KEY_CLAIM_COUNT: &[u8] = b"claim_count";
+
+/// Storage prefix under which default destinations are stored, by canonical address
+pub const PREFIX_CLAIM_DESTINATIONS: &[u8] = b"claim_destinations";
+
+/// A single claim.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct ClaimEntry<A> {
+    pub address:   A,
+    /// Where the claimed funds were sent
+    pub recipient: A,
+    /// Block time
+    pub time:      Seconds,
+    /// Block height
+    pub height:    u64,
+    pub amount:    Uint128,
+}
+impl ClaimEntry<CanonicalAddr> {
+    pub fn humanize <A: Api> (&self, api: &A) -> StdResult<ClaimEntry<HumanAddr>> {
+        Ok(ClaimEntry {
+            address:   api.human_address(&self.address)?,
+            recipient: api.human_address(&self.recipient)?,
+            time:      self.time,
+            height:    self.height,
+            amount:    self.amount
+        })
+    }
+}
+
+/// Number of entries in the log
+pub fn claim_count <S: ReadonlyStorage> (storage: &S) -> StdResult<u64> {
+    match storage.get(KEY_CLAIM_COUNT) {
+        Some(count) => from_slice(&count),
+        None => Ok(0)
+    }
+}
+
+/// Append an entry to the log, returning its index
+pub fn log_claim <S: Storage> (storage: &mut S, entry: &ClaimEntry<CanonicalAddr>) -> StdResult<u64> {
+    let index = claim_count(storage)?;
+    PrefixedStorage::new(PREFIX_CLAIMS, storage).set(&index.to_be_bytes(), &to_vec(entry)?);
+    storage.set(KEY_CLAIM_COUNT, &to_vec(&(index + 1))?);
+    Ok(index)
+}
+
+/// Up to `limit` entries, starting from the `start`-th
+pub fn get_claims <S: ReadonlyStorage> (
+    storage: &S, start: u64, limit: u64
+) -> StdResult<Vec<ClaimEntry<CanonicalAddr>>> {
+    let end = u64::min(start.saturating_add(limit), claim_count(storage)?);
+    let claims = ReadonlyPrefixedStorage::new(PREFIX_CLAIMS, storage);
+    let mut entries = vec![];
+    for index in start..end {
+        if let Some(entry) = claims.get(&index.to_be_bytes()) {
+            entries.push(from_slice(&entry)?)
+        }
+    }
+    Ok(entries)
+}
+
+/// Set the address to which the claims of `address` are sent by default,
+/// or go back to sending them to `address` itself.
+pub fn set_claim_destination <S: Storage> (
+    storage: &mut S, address: &CanonicalAddr, destination: Option<&CanonicalAddr>
+) {
+    let mut destinations = PrefixedStorage::new(PREFIX_CLAIM_DESTINATIONS, storage);
+    match destination {
+        Some(destination) => destinations.set(address.as_slice(), destination.as_slice()),
+        None => destinations.remove(address.as_slice())
+    }
+}
+
+/// The address to which the claims of `address` are sent by default, if not `address` itself.
+pub fn get_claim_destination <S: ReadonlyStorage> (
+    storage: &S, address: &CanonicalAddr
+) -> Option<CanonicalAddr> {
+    ReadonlyPrefixedStorage::new(PREFIX_CLAIM_DESTINATIONS, storage)
+        .get(address.as_slice())
+        .map(|bytes| CanonicalAddr(Binary(bytes)))
+}
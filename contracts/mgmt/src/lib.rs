@@ -4,11 +4,22 @@
 pub use secret_toolkit::snip20::handle::{mint_msg, transfer_msg, set_minters_msg, change_admin_msg};
 pub use sienna_migration::{ContractStatus, ContractStatusLevel, is_operational, can_set_status};
 pub use sienna_schedule::{
-    Seconds, Schedule, Pool, Account,
-    vesting::Vesting, validate::Validation, canon::{Humanize, Canonize}
+    Seconds, Schedule, Pool, Account, ScheduleError,
+    vesting::{Vesting, AccountUnlocked}, validate::Validation, canon::{Humanize, Canonize}
 };
 pub use linear_map::LinearMap;
 
+pub mod auth; use auth::{create_viewing_key, set_viewing_key, check_viewing_key};
+pub mod claims; pub use claims::ClaimEntry; use claims::{
+    log_claim, get_claims, claim_count, set_claim_destination, get_claim_destination
+};
+pub mod store; use store::{
+    save_schedule, update_schedule, load_schedule, load_schedule_for, schedule_total,
+    get_claimed, set_claimed, move_claimed, history_count, get_history,
+    get_proposal, set_proposal,
+    begin_staged, stage_pool, stage_accounts, load_staged, clear_staged
+};
+
 /// How much each recipient has claimed so far
 pub type History<T> = LinearMap<T, Uint128>;
 
@@ -21,6 +32,24 @@ pub type ContractLink<T> = (T, CodeHash);
 /// Whether the vesting process has begun and when.
 pub type Launched = Option<Seconds>;
 
+/// How much of a single account is unlocked and claimed (see the `Breakdown` query).
+/// What an address has claimed is attributed to its accounts in schedule order.
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, PartialEq, Debug, Clone)]
+pub struct AccountProgress {
+    pub pool:      String,
+    pub account:   String,
+    pub unlocked:  Uint128,
+    pub claimed:   Uint128,
+    /// How much of the account is yet to be claimed
+    pub remaining: Uint128,
+}
+
+/// Default number of entries per page of `History`
+pub const HISTORY_PAGE_SIZE: u32 = 30;
+
+/// Maximum number of entries per page of `History`
+pub const HISTORY_MAX_PAGE_SIZE: u32 = 100;
+
 /// Default value for Secret Network block size
 /// (according to Reuven on Discord; used for padding).
 pub const BLOCK_SIZE: usize = 256;
@@ -33,10 +62,17 @@ pub const BLOCK_SIZE: usize = 256;
     (PRELAUNCH)   => { "The vesting has not yet begun.".to_string() };
     (NOT_FOUND)   => { "Can't find account or pool by name".to_string() };
     (ADD_ACCOUNT) => { "Can't add account - pool full".to_string() };
+    (NO_PROPOSAL) => { "No change of address to the sender has been proposed".to_string() };
+    (HAS_CLAIMED) => { "The new address has already claimed funds".to_string() };
+    (NOT_STAGED)  => { "No schedule is being uploaded".to_string() };
+    (CLAIMED)     => { "The account's address has already claimed funds".to_string() };
 }
 
 contract!(
 
+    // The vesting configuration (which can be changed using `Configure`)
+    // and how much each address has received from the contract
+    // are stored outside of the state, under separate keys (see `store`).
     [State] {
         /// Starts out as the instantiatior of the contract, can be changed via `SetOwner`
         admin:    CanonicalAddr,
@@ -46,10 +82,6 @@ contract!(
         token:    ContractLink<CanonicalAddr>,
         /// When this contract is launched, this is set to the block time.
         launched: Launched,
-        /// How much each address has received from the contract.
-        history:  History<CanonicalAddr>,
-        /// Vesting configuration. Can be changed using `Configure`.
-        schedule: Schedule<CanonicalAddr>,
         /// The paused/migration flag.
         status:   ContractStatus
     }
@@ -59,11 +91,13 @@ contract!(
         history:  Option<History<HumanAddr>>,
         token:    ContractLink<HumanAddr>
     }) {
+        save_schedule(&mut deps.storage, &schedule.canonize(&deps.api)?)?;
+        for (address, claimed) in history.unwrap_or_default().canonize(&deps.api)?.0.iter() {
+            set_claimed(&mut deps.storage, address, claimed.u128())?;
+        }
         State {
             admin:    deps.api.canonical_address(&env.message.sender)?,
-            history:  history.unwrap_or_default().canonize(&deps.api)?,
             launched: None,
-            schedule: schedule.canonize(&deps.api)?,
             token:    (deps.api.canonical_address(&token.0)?, token.1),
             status:   ContractStatus::default()
         }
@@ -79,39 +113,138 @@ contract!(
             })
         }
 
-        /// Return schedule
-        Schedule () {
-            Ok(Response::Schedule { schedule: state.schedule.humanize(&deps.api)? })
+        /// Return the whole schedule (requires the admin's viewing key), or, if `address`
+        /// is given, only the accounts of that address, in the pools that contain them
+        /// (requires the viewing key of that address or of the admin)
+        Schedule (key: String, address: Option<HumanAddr>) {
+            let schedule = match address {
+                None => {
+                    is_authorized(&deps.storage, &state, &state.admin, &key)?;
+                    load_schedule(&deps.storage)?
+                },
+                Some(address) => {
+                    let address = deps.api.canonical_address(&address)?;
+                    is_authorized(&deps.storage, &state, &address, &key)?;
+                    load_schedule_for(&deps.storage, &address)?
+                }
+            };
+            Ok(Response::Schedule { schedule: schedule.humanize(&deps.api)? })
         }
 
-        /// Return claim history
-        History () {
-            Ok(Response::History { history: state.history.humanize(&deps.api)? })
+        /// Return a page of the claim history, in order of first claim,
+        /// and the total number of entries (requires the admin's viewing key)
+        History (key: String, start: Option<u64>, limit: Option<u32>) {
+            is_authorized(&deps.storage, &state, &state.admin, &key)?;
+            let limit = u32::min(limit.unwrap_or(HISTORY_PAGE_SIZE), HISTORY_MAX_PAGE_SIZE) as u64;
+            Ok(Response::History {
+                history: get_history(&deps.storage, start.unwrap_or(0), limit)?.humanize(&deps.api)?,
+                total:   history_count(&deps.storage)?
+            })
+        }
+
+        /// Return a page of the claim log, oldest first,
+        /// and the total number of entries (requires the admin's viewing key)
+        Claims (key: String, start: Option<u64>, limit: Option<u32>) {
+            is_authorized(&deps.storage, &state, &state.admin, &key)?;
+            let limit  = u32::min(limit.unwrap_or(HISTORY_PAGE_SIZE), HISTORY_MAX_PAGE_SIZE);
+            let claims = get_claims(&deps.storage, start.unwrap_or(0), limit as u64)?
+                .iter().map(|entry| entry.humanize(&deps.api)).collect::<StdResult<Vec<_>>>()?;
+            Ok(Response::Claims { claims, total: claim_count(&deps.storage)? })
+        }
+
+        /// Return how much the specified address has claimed so far
+        /// (requires the viewing key of that address or of the admin)
+        Claimed (address: HumanAddr, key: String) {
+            let address_canon = deps.api.canonical_address(&address)?;
+            is_authorized(&deps.storage, &state, &address_canon, &key)?;
+            let claimed = get_claimed(&deps.storage, &address_canon)?.into();
+            Ok(Response::Claimed { address, claimed })
         }
 
         /// Return amount that can be claimed by the specified address at the specified time
-        Progress (address: HumanAddr, time: Seconds) {
+        /// (requires the viewing key of that address or of the admin)
+        Progress (address: HumanAddr, time: Seconds, key: String) {
+            let address_human = address;
+            let address_canon = deps.api.canonical_address(&address_human)?;
+            is_authorized(&deps.storage, &state, &address_canon, &key)?;
             if let Some(launched) = &state.launched {
-                let address_human = address;
-                let address_canon = deps.api.canonical_address(&address_human)?;
                 let elapsed  = get_elapsed(time, *launched);
-                let unlocked = state.schedule.unlocked(elapsed, &address_canon).into();
-                let claimed  = match state.history.get(&address_canon) {
-                    Some(&claimed) => claimed,
-                    None => Uint128::zero()
-                };
+                let schedule = load_schedule_for(&deps.storage, &address_canon)?;
+                let unlocked = schedule.unlocked(elapsed, &address_canon)?.into();
+                let claimed  = get_claimed(&deps.storage, &address_canon)?.into();
                 Ok(Response::Progress { time, launched: *launched, elapsed, unlocked, claimed })
             } else {
                 Ok(Response::Error { msg: MGMTError!(PRELAUNCH).to_string() })
             }
         }
+
+        /// Return how much each of the accounts of the specified address
+        /// has unlocked and how much of that was claimed
+        /// (requires the viewing key of that address or of the admin)
+        Breakdown (address: HumanAddr, time: Seconds, key: String) {
+            let address = deps.api.canonical_address(&address)?;
+            is_authorized(&deps.storage, &state, &address, &key)?;
+            if let Some(launched) = &state.launched {
+                let elapsed     = get_elapsed(time, *launched);
+                let mut claimed = get_claimed(&deps.storage, &address)?;
+                let mut accounts = vec![];
+                for AccountUnlocked { pool, account, unlocked, allocated } in
+                    load_schedule_for(&deps.storage, &address)?.breakdown(elapsed, &address)?
+                {
+                    let account_claimed = u128::min(claimed, unlocked.u128());
+                    claimed -= account_claimed;
+                    accounts.push(AccountProgress {
+                        pool,
+                        account,
+                        unlocked,
+                        claimed:   account_claimed.into(),
+                        remaining: (allocated.u128() - account_claimed).into()
+                    })
+                }
+                Ok(Response::Breakdown { time, launched: *launched, elapsed, accounts })
+            } else {
+                Ok(Response::Error { msg: MGMTError!(PRELAUNCH).to_string() })
+            }
+        }
+
+        /// Return when the next portion for the specified address unlocks and how big it is,
+        /// when the last one unlocks, and how much is still locked at the specified time
+        /// (requires the viewing key of that address or of the admin)
+        NextUnlock (address: HumanAddr, time: Seconds, key: String) {
+            let address = deps.api.canonical_address(&address)?;
+            is_authorized(&deps.storage, &state, &address, &key)?;
+            if let Some(launched) = &state.launched {
+                let elapsed  = get_elapsed(time, *launched);
+                let schedule = load_schedule_for(&deps.storage, &address)?;
+                let unlocked = schedule.unlocked(elapsed, &address)?;
+                let total    = schedule.unlocked(Seconds::MAX, &address)?;
+                let (next_time, next_amount) = match schedule.next_unlock(elapsed, &address)? {
+                    Some((t, amount)) => (Some(launched.saturating_add(t)), Uint128::from(amount)),
+                    None => (None, Uint128::zero())
+                };
+                let final_time = schedule.timeline_for(&address)?.last()
+                    .map(|e| launched.saturating_add(e.event.time));
+                let locked = Uint128::from(total - unlocked);
+                Ok(Response::NextUnlock { time, next_time, next_amount, final_time, locked })
+            } else {
+                Ok(Response::Error { msg: MGMTError!(PRELAUNCH).to_string() })
+            }
+        }
     }
 
     [Response] {
         Status   { launched: Launched, token: ContractLink<HumanAddr>, status: ContractStatus }
         Schedule { schedule: Schedule<HumanAddr> }
-        History  { history: History<HumanAddr> }
+        History  { history: History<HumanAddr>, total: u64 }
+        Claimed  { address: HumanAddr, claimed: Uint128 }
+        Claims   { claims: Vec<ClaimEntry<HumanAddr>>, total: u64 }
         Progress { time: Seconds, launched: Seconds, elapsed: Seconds, unlocked: Uint128, claimed: Uint128 }
+        Breakdown { time: Seconds, launched: Seconds, elapsed: Seconds, accounts: Vec<AccountProgress> }
+        NextUnlock {
+            time: Seconds, next_time: Option<Seconds>, next_amount: Uint128,
+            final_time: Option<Seconds>, locked: Uint128
+        }
+        ViewingKey { key: String }
         Error    { msg: String }
         NotFound {}
     }
@@ -154,6 +287,37 @@ contract!(
             Ok(HandleResponse::default())
         }
 
+        /// Set the viewing key with which the sender's progress can be queried.
+        SetViewingKey (key: String) {
+            let address = deps.api.canonical_address(&env.message.sender)?;
+            set_viewing_key(&mut deps.storage, &address, &key);
+            Ok(HandleResponse::default())
+        }
+
+        /// Generate a viewing key for the sender and return it.
+        CreateViewingKey (entropy: String) {
+            let address = deps.api.canonical_address(&env.message.sender)?;
+            let key = create_viewing_key(&env, &entropy);
+            set_viewing_key(&mut deps.storage, &address, &key);
+            Ok(HandleResponse {
+                messages: vec![],
+                log:      vec![],
+                data:     Some(to_binary(&Response::ViewingKey { key })?)
+            })
+        }
+
+        /// Set where the sender's claims are sent when `Claim` doesn't specify a recipient.
+        /// `None` goes back to sending them to the sender.
+        SetClaimDestination (destination: Option<HumanAddr>) {
+            let address = deps.api.canonical_address(&env.message.sender)?;
+            let destination = match destination {
+                Some(destination) => Some(deps.api.canonical_address(&destination)?),
+                None => None
+            };
+            set_claim_destination(&mut deps.storage, &address, destination.as_ref());
+            Ok(HandleResponse::default())
+        }
+
         /// Load a new schedule (only before launching the contract)
         Configure (schedule: Schedule<HumanAddr>) {
             is_admin(&deps.api, &state, &env)?;
@@ -161,9 +325,70 @@ contract!(
             is_not_launched(&state)?;
 
             schedule.validate()?;
-            state.schedule = schedule.canonize(&deps.api)?;
+            save_schedule(&mut deps.storage, &schedule.canonize(&deps.api)?)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Begin uploading a schedule that adds up to `total` in several transactions,
+        /// for schedules that are too big for `Configure` (only before launching the contract).
+        /// Pools and accounts are added with `AppendPool` and `AppendAccounts`, and the
+        /// schedule is validated and takes effect on `FinalizeConfigure`.
+        /// Discards any other schedule being uploaded.
+        BeginConfigure (total: Uint128) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+            is_not_launched(&state)?;
+
+            begin_staged(&mut deps.storage, total)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Add a pool, with some or all of its accounts, to the schedule being uploaded
+        AppendPool (pool: Pool<HumanAddr>) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+            is_not_launched(&state)?;
+
+            stage_pool(&mut deps.storage, &pool.canonize(&deps.api)?)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Add accounts to a pool of the schedule being uploaded
+        AppendAccounts (pool_name: String, accounts: Vec<Account<HumanAddr>>) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+            is_not_launched(&state)?;
+
+            stage_accounts(&mut deps.storage, &pool_name, accounts.canonize(&deps.api)?)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Validate the uploaded schedule and replace the current one with it.
+        /// If it is invalid, it stays uploaded, so that it can be amended.
+        FinalizeConfigure () {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+            is_not_launched(&state)?;
+
+            let schedule = load_staged(&deps.storage)?;
+            schedule.validate()?;
+            save_schedule(&mut deps.storage, &schedule)?;
+            clear_staged(&mut deps.storage)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Discard the schedule being uploaded
+        AbortConfigure () {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+
+            clear_staged(&mut deps.storage)?;
 
-            save_state!();
             Ok(HandleResponse::default())
         }
 
@@ -173,12 +398,124 @@ contract!(
             is_operational(&state.status)?;
 
             let account = account.canonize(&deps.api)?;
-            state.schedule.add_account(&pool_name, account)?;
+            let previous = load_schedule(&deps.storage)?;
+            let mut schedule = previous.clone();
+            schedule.add_account(&pool_name, account)?;
+            update_schedule(&mut deps.storage, &previous, &schedule)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Add several new accounts to partially filled pools, as `(pool_name, account)` pairs.
+        /// If any of them can't be added, none are.
+        AddAccounts (accounts: Vec<(String, Account<HumanAddr>)>) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+
+            let previous = load_schedule(&deps.storage)?;
+            let mut schedule = previous.clone();
+            for (pool_name, account) in accounts.iter() {
+                schedule.add_account(pool_name, account.canonize(&deps.api)?)?;
+            }
+            update_schedule(&mut deps.storage, &previous, &schedule)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Remove an account that hasn't unlocked anything yet,
+        /// and whose address hasn't claimed anything. Its funds go back
+        /// to the pool, which becomes partial.
+        RemoveAccount (pool_name: String, account_name: String) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+
+            let elapsed = state.launched.map(|launched| get_elapsed(env.block.time, launched));
+            let previous = load_schedule(&deps.storage)?;
+            let mut schedule = previous.clone();
+            is_unclaimed(&deps.storage, &schedule, &pool_name, &account_name)?;
+            schedule.remove_account(&pool_name, &account_name, elapsed)?;
+            update_schedule(&mut deps.storage, &previous, &schedule)?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Replace an account that hasn't unlocked anything yet,
+        /// and whose address hasn't claimed anything, with `account`.
+        UpdateAccount (pool_name: String, account_name: String, account: Account<HumanAddr>) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+
+            let elapsed = state.launched.map(|launched| get_elapsed(env.block.time, launched));
+            let previous = load_schedule(&deps.storage)?;
+            let mut schedule = previous.clone();
+            is_unclaimed(&deps.storage, &schedule, &pool_name, &account_name)?;
+            schedule.update_account(&pool_name, &account_name, account.canonize(&deps.api)?, elapsed)?;
+            update_schedule(&mut deps.storage, &previous, &schedule)?;
 
-            save_state!();
             Ok(HandleResponse::default())
         }
 
+        /// Stop the vesting of an account. What has already vested can still
+        /// be claimed; the rest goes back to the pool, which becomes partial
+        /// so that the funds can be allocated to a new account.
+        Revoke (pool: String, account: String) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+
+            let launched = is_launched(&state)?;
+            let elapsed  = get_elapsed(env.block.time, launched);
+            let previous = load_schedule(&deps.storage)?;
+            let mut schedule = previous.clone();
+            schedule.revoke(&pool, &account, elapsed)?;
+            update_schedule(&mut deps.storage, &previous, &schedule)?;
+
+            Ok(HandleResponse { messages: vec![], data: None, log: vec![
+                LogAttribute { key: "revoked".to_string(), value: elapsed.to_string() }
+            ] })
+        }
+
+        /// Propose to move all of the sender's accounts, and the record of
+        /// what they have claimed so far, to `new_address` (e.g. if the sender's
+        /// key is compromised). Takes effect when `new_address` calls `AcceptAddress`.
+        /// Replaces any previous proposal by the sender.
+        ProposeAddress (new_address: HumanAddr) {
+            is_operational(&state.status)?;
+
+            let address     = deps.api.canonical_address(&env.message.sender)?;
+            let new_address = deps.api.canonical_address(&new_address)?;
+            set_proposal(&mut deps.storage, &address, Some(&new_address))?;
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Accept a change of address proposed by `address`, taking over its accounts.
+        AcceptAddress (address: HumanAddr) {
+            is_operational(&state.status)?;
+
+            let address     = deps.api.canonical_address(&address)?;
+            let new_address = deps.api.canonical_address(&env.message.sender)?;
+            match get_proposal(&deps.storage, &address)? {
+                Some(proposed) if proposed == new_address => {},
+                _ => return Err(StdError::GenericErr { msg: MGMTError!(NO_PROPOSAL), backtrace: None })
+            }
+            change_address(&mut deps.storage, &address, &new_address)?;
+
+            Ok(HandleResponse { messages: vec![], data: None, log: address_changed(&deps.api, &address, &new_address)? })
+        }
+
+        /// Move all accounts of `address`, and the record of what it has claimed
+        /// so far, to `new_address`, without the involvement of either.
+        ChangeAddress (address: HumanAddr, new_address: HumanAddr) {
+            is_admin(&deps.api, &state, &env)?;
+            is_operational(&state.status)?;
+
+            let address     = deps.api.canonical_address(&address)?;
+            let new_address = deps.api.canonical_address(&new_address)?;
+            change_address(&mut deps.storage, &address, &new_address)?;
+
+            Ok(HandleResponse { messages: vec![], data: None, log: address_changed(&deps.api, &address, &new_address)? })
+        }
+
         /// An instance can be launched only once.
         /// Launching the instance mints the total tokens as specified by
         /// the schedule, and prevents any more tokens from ever being minted
@@ -189,7 +526,8 @@ contract!(
             is_operational(&state.status)?;
 
             state.launched = Some(env.block.time);
-            let messages = mint_and_clear_minters(&deps.api, &state, &env)?;
+            let total    = schedule_total(&deps.storage)?;
+            let messages = mint_and_clear_minters(&deps.api, &state, &env, total)?;
 
             save_state!();
             Ok(HandleResponse { messages, data: None, log: vec![
@@ -199,19 +537,44 @@ contract!(
 
         /// After launch, recipients can call the Claim method to
         /// receive the gains that they have accumulated so far.
-        Claim () {
+        /// These are sent to `recipient` if specified, otherwise to the
+        /// sender's default destination (see `SetClaimDestination`) if set,
+        /// otherwise to the sender. If `amount` is specified, only that much
+        /// (or everything that is claimable, whichever is less) is claimed.
+        Claim (recipient: Option<HumanAddr>, amount: Option<Uint128>) {
             is_operational(&state.status)?;
 
             let launched = is_launched(&state)?;
             let elapsed  = get_elapsed(env.block.time, launched);
             let claimant = deps.api.canonical_address(&env.message.sender)?;
-            let (unlocked, claimable) = portion(&state, &claimant, elapsed);
-            if claimable > 0 {
-                state.history.insert(claimant.clone(), unlocked.into());
-                let messages = vec![transfer(&deps.api, &state, &claimant, claimable.into())?];
-
-                save_state!();
-                Ok(HandleResponse { messages, data: None, log: vec![] })
+            let (claimed, claimable) = portion(&deps.storage, &claimant, elapsed)?;
+            let amount = match amount {
+                Some(amount) => u128::min(amount.u128(), claimable),
+                None => claimable
+            };
+            if amount > 0 {
+                let recipient = match recipient {
+                    Some(recipient) => deps.api.canonical_address(&recipient)?,
+                    None => get_claim_destination(&deps.storage, &claimant)
+                        .unwrap_or_else(|| claimant.clone())
+                };
+                set_claimed(&mut deps.storage, &claimant, claimed + amount)?;
+                let messages = vec![transfer(&deps.api, &state, &recipient, amount.into())?];
+                let log = vec![
+                    LogAttribute { key: "claimant".to_string(),  value: env.message.sender.to_string() },
+                    LogAttribute { key: "recipient".to_string(), value: deps.api.human_address(&recipient)?.to_string() },
+                    LogAttribute { key: "claimed".to_string(),   value: amount.to_string() },
+                    LogAttribute { key: "time".to_string(),      value: env.block.time.to_string() },
+                ];
+                log_claim(&mut deps.storage, &ClaimEntry {
+                    address:   claimant,
+                    recipient,
+                    time:      env.block.time,
+                    height:    env.block.height,
+                    amount:    amount.into()
+                })?;
+
+                Ok(HandleResponse { messages, data: None, log })
             } else {
                 Err(StdError::GenericErr { msg: MGMTError!(NOTHING), backtrace: None })
             }
@@ -226,6 +589,16 @@ fn is_admin <A:Api> (api: &A, state: &State, env: &Env) -> StdResult<()> {
     Err(StdError::Unauthorized { backtrace: None })
 }
 
+/// Queries about an address can be made with its own viewing key or with the admin's
+fn is_authorized <S:Storage> (
+    storage: &S, state: &State, address: &CanonicalAddr, key: &str
+) -> StdResult<()> {
+    if check_viewing_key(storage, address, key) || check_viewing_key(storage, &state.admin, key) {
+        return Ok(())
+    }
+    Err(StdError::Unauthorized { backtrace: None })
+}
+
 fn is_not_launched (state: &State) -> StdResult<()> {
     match state.launched {
         None => Ok(()),
@@ -246,6 +619,20 @@ fn is_launched (state: &State) -> StdResult<Seconds> {
     }
 }
 
+/// Fails if the address of the specified account has claimed anything.
+fn is_unclaimed <S:Storage> (
+    storage: &S, schedule: &Schedule<CanonicalAddr>, pool_name: &str, account_name: &str
+) -> StdResult<()> {
+    for pool in schedule.pools.iter().filter(|pool| pool.name == pool_name) {
+        for account in pool.accounts.iter().filter(|account| account.name == account_name) {
+            if get_claimed(storage, &account.address)? > 0 {
+                return Err(StdError::GenericErr { msg: MGMTError!(CLAIMED), backtrace: None })
+            }
+        }
+    }
+    Ok(())
+}
+
 fn get_elapsed (t1: Seconds, t2: Seconds) -> Seconds {
     if t1 > t2 {
         t1 - t2
@@ -254,26 +641,44 @@ fn get_elapsed (t1: Seconds, t2: Seconds) -> Seconds {
     }
 }
 
-fn portion (state: &State, address: &CanonicalAddr, elapsed: Seconds) -> (u128, u128) {
-    let unlocked = state.schedule.unlocked(elapsed, &address);
-    if unlocked > 0 {
-        let claimed = match state.history.get(&address.clone().into()) {
-            Some(claimed) => claimed.u128(),
-            None => 0
-        };
-        if unlocked > claimed {
-            return (unlocked, unlocked - claimed);
-        }
-    }
-    return (unlocked, 0)
+/// How much `address` has claimed so far, and how much more it can claim.
+/// Only loads the accounts of `address`.
+fn portion <S:Storage> (storage: &S, address: &CanonicalAddr, elapsed: Seconds) -> StdResult<(u128, u128)> {
+    let unlocked = load_schedule_for(storage, address)?.unlocked(elapsed, address)?;
+    let claimed  = get_claimed(storage, address)?;
+    Ok((claimed, unlocked.saturating_sub(claimed)))
+}
+
+/// Move the schedule entries and the history record of `address` to `new_address`,
+/// and discard any pending proposal by `address`.
+fn change_address <S:Storage> (
+    storage: &mut S, address: &CanonicalAddr, new_address: &CanonicalAddr
+) -> StdResult<()> {
+    let previous = load_schedule(storage)?;
+    let mut schedule = previous.clone();
+    schedule.change_address(address, new_address)?;
+    update_schedule(storage, &previous, &schedule)?;
+    move_claimed(storage, address, new_address)?;
+    set_proposal(storage, address, None)
+}
+
+fn address_changed <A:Api> (
+    api: &A, address: &CanonicalAddr, new_address: &CanonicalAddr
+) -> StdResult<Vec<LogAttribute>> {
+    Ok(vec![
+        LogAttribute { key: "address".to_string(),     value: api.human_address(address)?.to_string() },
+        LogAttribute { key: "new_address".to_string(), value: api.human_address(new_address)?.to_string() },
+    ])
 }
 
-fn mint_and_clear_minters <A:Api> (api: &A, state: &State, env: &Env) -> StdResult<Vec<CosmosMsg>> {
+fn mint_and_clear_minters <A:Api> (
+    api: &A, state: &State, env: &Env, total: Uint128
+) -> StdResult<Vec<CosmosMsg>> {
     let (addr_canon, hash) = state.token.clone();
     let addr_human = api.human_address(&addr_canon)?;
     Ok(vec![
         mint_msg(
-            env.contract.address.clone(), state.schedule.total,
+            env.contract.address.clone(), total,
             None, BLOCK_SIZE, hash.clone(), addr_human.clone()
         )?,
         set_minters_msg(
@@ -0,0 +1,393 @@
+//! Per-key storage of the schedule, the history, and proposed changes of address
+//! (and of schedules being uploaded in several transactions)
+//!
+//! If these were part of the contract's state, every transaction would have to load
+//! and save all of them, so the cost of a claim would grow with the number of accounts.
+//! Instead, each pool, account and history entry is stored under its own key, and
+//! each address is indexed to its accounts, so that a claim only reads and writes
+//! the entries of the claimant, and changing the schedule only rewrites the entries
+//! that changed.
+
+use cosmwasm_std::{
+    Storage, ReadonlyStorage, StdResult, StdError, Uint128, CanonicalAddr, to_vec, from_slice
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use sienna_schedule::{Schedule, Pool, Account, ScheduleError};
+use crate::History;
+
+/// Storage key under which the schedule's total and number of pools are stored
+pub const KEY_SCHEDULE: &[u8] = b"schedule";
+
+/// Storage prefix under which pools (without their accounts) are stored, by index
+pub const PREFIX_POOLS: &[u8] = b"pools";
+
+/// Storage prefix under which accounts are stored, by pool index and account index
+pub const PREFIX_ACCOUNTS: &[u8] = b"accounts";
+
+/// Storage prefix under which the locations of each address's accounts are stored
+pub const PREFIX_ADDRESSES: &[u8] = b"addresses";
+
+/// Storage prefix under which how much each address has claimed is stored
+pub const PREFIX_HISTORY: &[u8] = b"history";
+
+/// Storage prefix under which the address of each history entry is stored, by index
+pub const PREFIX_HISTORY_INDEX: &[u8] = b"history_index";
+
+/// Storage key under which the number of history entries is stored
+pub const KEY_HISTORY_COUNT: &[u8] = b"history_count";
+
+/// Storage prefix under which proposed changes of address are stored, by current address
+pub const PREFIX_PROPOSALS: &[u8] = b"proposals";
+
+/// Storage key under which the total and number of pools of a schedule being uploaded are stored
+pub const KEY_STAGED: &[u8] = b"staged";
+
+/// Storage prefix under which the pools (with their accounts) of a schedule
+/// being uploaded are stored, by index
+pub const PREFIX_STAGED_POOLS: &[u8] = b"staged_pools";
+
+#[derive(Serialize, Deserialize)]
+struct ScheduleHeader {
+    total: Uint128,
+    pools: u32
+}
+
+#[derive(Serialize, Deserialize)]
+struct PoolHeader {
+    partial:  bool,
+    name:     String,
+    total:    Uint128,
+    accounts: u32
+}
+
+#[derive(Serialize, Deserialize)]
+struct HistoryEntry {
+    /// Position in the history (order of first claim)
+    index:   u64,
+    claimed: Uint128
+}
+
+/// Location of an account: index of pool, index of account in pool
+type AccountIndex = (u32, u32);
+
+/// Replace the stored schedule with `schedule`.
+pub fn save_schedule <S: Storage> (storage: &mut S, schedule: &Schedule<CanonicalAddr>) -> StdResult<()> {
+    let previous = load_schedule(storage)?;
+    update_schedule(storage, &previous, schedule)
+}
+
+/// Replace the stored schedule, which is `previous`, with `schedule`,
+/// writing only what differs between them: the pool headers and accounts that changed,
+/// and the index entries of the addresses whose accounts were added, removed or moved.
+/// (Removing an account moves the ones after it in the same pool.)
+pub fn update_schedule <S: Storage> (
+    storage: &mut S, previous: &Schedule<CanonicalAddr>, schedule: &Schedule<CanonicalAddr>
+) -> StdResult<()> {
+    if previous.total != schedule.total || previous.pools.len() != schedule.pools.len() {
+        storage.set(KEY_SCHEDULE, &to_vec(&ScheduleHeader {
+            total: schedule.total,
+            pools: schedule.pools.len() as u32
+        })?);
+    }
+    let no_accounts = vec![];
+    let mut moved: Vec<&CanonicalAddr> = vec![];
+    for i in 0..usize::max(previous.pools.len(), schedule.pools.len()) {
+        let old_pool = previous.pools.get(i);
+        let new_pool = schedule.pools.get(i);
+        let old_accounts = old_pool.map_or(&no_accounts, |pool| &pool.accounts);
+        let new_accounts = new_pool.map_or(&no_accounts, |pool| &pool.accounts);
+        let pool_key = (i as u32).to_be_bytes();
+        match (old_pool, new_pool) {
+            (Some(old), Some(pool)) if same_header(old, pool) => {},
+            (_, Some(pool)) => save(storage, PREFIX_POOLS, &pool_key, &PoolHeader {
+                partial:  pool.partial,
+                name:     pool.name.clone(),
+                total:    pool.total,
+                accounts: pool.accounts.len() as u32
+            })?,
+            (_, None) => PrefixedStorage::new(PREFIX_POOLS, storage).remove(&pool_key)
+        }
+        for j in 0..usize::max(old_accounts.len(), new_accounts.len()) {
+            let key = account_key(i as u32, j as u32);
+            match (old_accounts.get(j), new_accounts.get(j)) {
+                (Some(old), Some(new)) => if old != new {
+                    save(storage, PREFIX_ACCOUNTS, &key, new)?;
+                    if old.address != new.address {
+                        moved.push(&old.address);
+                        moved.push(&new.address);
+                    }
+                },
+                (None, Some(new)) => {
+                    save(storage, PREFIX_ACCOUNTS, &key, new)?;
+                    moved.push(&new.address);
+                },
+                (Some(old), None) => {
+                    PrefixedStorage::new(PREFIX_ACCOUNTS, storage).remove(&key);
+                    moved.push(&old.address);
+                },
+                (None, None) => {}
+            }
+        }
+    }
+    for (n, address) in moved.iter().enumerate() {
+        if moved[..n].contains(address) {
+            continue
+        }
+        let index = locate(schedule, address);
+        if index.is_empty() {
+            PrefixedStorage::new(PREFIX_ADDRESSES, storage).remove(address.as_slice());
+        } else {
+            save(storage, PREFIX_ADDRESSES, address.as_slice(), &index)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether two pools have the same stored header
+fn same_header (a: &Pool<CanonicalAddr>, b: &Pool<CanonicalAddr>) -> bool {
+    a.partial == b.partial && a.name == b.name && a.total == b.total && a.accounts.len() == b.accounts.len()
+}
+
+/// Locations of the accounts of `address` in `schedule`
+fn locate (schedule: &Schedule<CanonicalAddr>, address: &CanonicalAddr) -> Vec<AccountIndex> {
+    let mut index = vec![];
+    for (i, pool) in schedule.pools.iter().enumerate() {
+        for (j, account) in pool.accounts.iter().enumerate() {
+            if account.address == *address {
+                index.push((i as u32, j as u32));
+            }
+        }
+    }
+    index
+}
+
+/// Load the whole schedule.
+pub fn load_schedule <S: ReadonlyStorage> (storage: &S) -> StdResult<Schedule<CanonicalAddr>> {
+    let header = schedule_header(storage)?;
+    let mut pools = vec![];
+    for i in 0..header.pools {
+        let pool = pool_header(storage, i)?;
+        let mut accounts = vec![];
+        for j in 0..pool.accounts {
+            accounts.push(account(storage, i, j)?)
+        }
+        pools.push(Pool { partial: pool.partial, name: pool.name, total: pool.total, accounts })
+    }
+    Ok(Schedule { total: header.total, pools })
+}
+
+/// Load only the accounts of `address`, in the pools that contain them.
+/// The result does not pass validation, but vests the same amounts for `address`
+/// as the whole schedule would.
+pub fn load_schedule_for <S: ReadonlyStorage> (
+    storage: &S, address: &CanonicalAddr
+) -> StdResult<Schedule<CanonicalAddr>> {
+    let mut pools: Vec<(u32, Pool<CanonicalAddr>)> = vec![];
+    for (i, j) in account_index(storage, address)? {
+        let account = account(storage, i, j)?;
+        match pools.iter_mut().find(|(index, _)| *index == i) {
+            Some((_, pool)) => pool.accounts.push(account),
+            None => {
+                let pool = pool_header(storage, i)?;
+                pools.push((i, Pool {
+                    partial:  pool.partial,
+                    name:     pool.name,
+                    total:    pool.total,
+                    accounts: vec![account]
+                }))
+            }
+        }
+    }
+    Ok(Schedule {
+        total: schedule_header(storage)?.total,
+        pools: pools.into_iter().map(|(_, pool)| pool).collect()
+    })
+}
+
+/// The total of the stored schedule.
+pub fn schedule_total <S: ReadonlyStorage> (storage: &S) -> StdResult<Uint128> {
+    Ok(schedule_header(storage)?.total)
+}
+
+/// How much `address` has claimed so far.
+pub fn get_claimed <S: ReadonlyStorage> (storage: &S, address: &CanonicalAddr) -> StdResult<u128> {
+    Ok(match load::<HistoryEntry, _>(storage, PREFIX_HISTORY, address.as_slice())? {
+        Some(entry) => entry.claimed.u128(),
+        None => 0
+    })
+}
+
+/// Record that `address` has claimed `claimed` so far.
+pub fn set_claimed <S: Storage> (storage: &mut S, address: &CanonicalAddr, claimed: u128) -> StdResult<()> {
+    let index = match load::<HistoryEntry, _>(storage, PREFIX_HISTORY, address.as_slice())? {
+        Some(entry) => entry.index,
+        None => {
+            let index = history_count(storage)?;
+            save(storage, PREFIX_HISTORY_INDEX, &index.to_be_bytes(), address)?;
+            storage.set(KEY_HISTORY_COUNT, &to_vec(&(index + 1))?);
+            index
+        }
+    };
+    save(storage, PREFIX_HISTORY, address.as_slice(), &HistoryEntry { index, claimed: claimed.into() })
+}
+
+/// Move the history entry of `address` (if any) to `new_address`, keeping its position.
+/// Fails if `new_address` already has an entry.
+pub fn move_claimed <S: Storage> (
+    storage: &mut S, address: &CanonicalAddr, new_address: &CanonicalAddr
+) -> StdResult<()> {
+    if load::<HistoryEntry, _>(storage, PREFIX_HISTORY, new_address.as_slice())?.is_some() {
+        return Err(StdError::GenericErr { msg: crate::MGMTError!(HAS_CLAIMED), backtrace: None })
+    }
+    if let Some(entry) = load::<HistoryEntry, _>(storage, PREFIX_HISTORY, address.as_slice())? {
+        save(storage, PREFIX_HISTORY_INDEX, &entry.index.to_be_bytes(), new_address)?;
+        save(storage, PREFIX_HISTORY, new_address.as_slice(), &entry)?;
+        PrefixedStorage::new(PREFIX_HISTORY, storage).remove(address.as_slice());
+    }
+    Ok(())
+}
+
+/// Number of addresses that have claimed
+pub fn history_count <S: ReadonlyStorage> (storage: &S) -> StdResult<u64> {
+    match storage.get(KEY_HISTORY_COUNT) {
+        Some(count) => from_slice(&count),
+        None => Ok(0)
+    }
+}
+
+/// Up to `limit` history entries, starting from the `start`-th
+pub fn get_history <S: ReadonlyStorage> (
+    storage: &S, start: u64, limit: u64
+) -> StdResult<History<CanonicalAddr>> {
+    let end = u64::min(start.saturating_add(limit), history_count(storage)?);
+    let mut history = History::new();
+    for index in start..end {
+        let address: CanonicalAddr = load(storage, PREFIX_HISTORY_INDEX, &index.to_be_bytes())?
+            .ok_or_else(corrupted)?;
+        history.0.push((address.clone(), get_claimed(storage, &address)?.into()))
+    }
+    Ok(history)
+}
+
+/// The address to which `address` has proposed to move its accounts, if any.
+pub fn get_proposal <S: ReadonlyStorage> (
+    storage: &S, address: &CanonicalAddr
+) -> StdResult<Option<CanonicalAddr>> {
+    load(storage, PREFIX_PROPOSALS, address.as_slice())
+}
+
+/// Set or discard the address to which `address` has proposed to move its accounts.
+pub fn set_proposal <S: Storage> (
+    storage: &mut S, address: &CanonicalAddr, new_address: Option<&CanonicalAddr>
+) -> StdResult<()> {
+    match new_address {
+        Some(new_address) => save(storage, PREFIX_PROPOSALS, address.as_slice(), new_address)?,
+        None => PrefixedStorage::new(PREFIX_PROPOSALS, storage).remove(address.as_slice())
+    }
+    Ok(())
+}
+
+/// Begin uploading a schedule that should add up to `total`,
+/// discarding any other schedule being uploaded.
+pub fn begin_staged <S: Storage> (storage: &mut S, total: Uint128) -> StdResult<()> {
+    clear_staged(storage)?;
+    storage.set(KEY_STAGED, &to_vec(&ScheduleHeader { total, pools: 0 })?);
+    Ok(())
+}
+
+/// Add a pool to the schedule being uploaded.
+pub fn stage_pool <S: Storage> (storage: &mut S, pool: &Pool<CanonicalAddr>) -> StdResult<()> {
+    let mut header = staged_header(storage)?;
+    save(storage, PREFIX_STAGED_POOLS, &header.pools.to_be_bytes(), pool)?;
+    header.pools += 1;
+    storage.set(KEY_STAGED, &to_vec(&header)?);
+    Ok(())
+}
+
+/// Add accounts to a pool of the schedule being uploaded.
+pub fn stage_accounts <S: Storage> (
+    storage: &mut S, pool_name: &str, accounts: Vec<Account<CanonicalAddr>>
+) -> StdResult<()> {
+    for i in 0..staged_header(storage)?.pools {
+        let mut pool: Pool<CanonicalAddr> = load(storage, PREFIX_STAGED_POOLS, &i.to_be_bytes())?
+            .ok_or_else(corrupted)?;
+        if pool.name == pool_name {
+            pool.accounts.extend(accounts);
+            return save(storage, PREFIX_STAGED_POOLS, &i.to_be_bytes(), &pool)
+        }
+    }
+    Err(ScheduleError::PoolNotFound { pool: pool_name.to_string() }.into())
+}
+
+/// Load the schedule being uploaded.
+pub fn load_staged <S: ReadonlyStorage> (storage: &S) -> StdResult<Schedule<CanonicalAddr>> {
+    let header = staged_header(storage)?;
+    let mut pools = vec![];
+    for i in 0..header.pools {
+        pools.push(load(storage, PREFIX_STAGED_POOLS, &i.to_be_bytes())?.ok_or_else(corrupted)?)
+    }
+    Ok(Schedule { total: header.total, pools })
+}
+
+/// Discard the schedule being uploaded, if any.
+pub fn clear_staged <S: Storage> (storage: &mut S) -> StdResult<()> {
+    if storage.get(KEY_STAGED).is_none() {
+        return Ok(())
+    }
+    for i in 0..staged_header(storage)?.pools {
+        PrefixedStorage::new(PREFIX_STAGED_POOLS, storage).remove(&i.to_be_bytes());
+    }
+    storage.remove(KEY_STAGED);
+    Ok(())
+}
+
+fn staged_header <S: ReadonlyStorage> (storage: &S) -> StdResult<ScheduleHeader> {
+    match storage.get(KEY_STAGED) {
+        Some(header) => from_slice(&header),
+        None => Err(StdError::GenericErr { msg: crate::MGMTError!(NOT_STAGED), backtrace: None })
+    }
+}
+
+fn schedule_header <S: ReadonlyStorage> (storage: &S) -> StdResult<ScheduleHeader> {
+    match storage.get(KEY_SCHEDULE) {
+        Some(header) => from_slice(&header),
+        None => Ok(ScheduleHeader { total: Uint128::zero(), pools: 0 })
+    }
+}
+
+fn pool_header <S: ReadonlyStorage> (storage: &S, i: u32) -> StdResult<PoolHeader> {
+    load(storage, PREFIX_POOLS, &i.to_be_bytes())?.ok_or_else(corrupted)
+}
+
+fn account <S: ReadonlyStorage> (storage: &S, i: u32, j: u32) -> StdResult<Account<CanonicalAddr>> {
+    load(storage, PREFIX_ACCOUNTS, &account_key(i, j))?.ok_or_else(corrupted)
+}
+
+fn account_index <S: ReadonlyStorage> (storage: &S, address: &CanonicalAddr) -> StdResult<Vec<AccountIndex>> {
+    Ok(load(storage, PREFIX_ADDRESSES, address.as_slice())?.unwrap_or_default())
+}
+
+fn account_key (i: u32, j: u32) -> Vec<u8> {
+    [i.to_be_bytes(), j.to_be_bytes()].concat()
+}
+
+fn load <T: DeserializeOwned, S: ReadonlyStorage> (
+    storage: &S, prefix: &[u8], key: &[u8]
+) -> StdResult<Option<T>> {
+    match ReadonlyPrefixedStorage::new(prefix, storage).get(key) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None)
+    }
+}
+
+fn save <T: Serialize, S: Storage> (
+    storage: &mut S, prefix: &[u8], key: &[u8], value: &T
+) -> StdResult<()> {
+    PrefixedStorage::new(prefix, storage).set(key, &to_vec(value)?);
+    Ok(())
+}
+
+fn corrupted () -> StdError {
+    StdError::GenericErr { msg: crate::MGMTError!(CORRUPTED), backtrace: None }
+}
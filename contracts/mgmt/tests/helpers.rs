@@ -37,9 +37,21 @@ pub fn harness (balances: &[(&HumanAddr, &[Coin])])-> ExternMock {
         }
     ).unwrap();
     assert_eq!(0, res.messages.len());
+    for (address, _) in balances.iter() { // everyone gets a viewing key
+        sienna_mgmt::handle(
+            &mut deps,
+            mock_env(0, 0, address),
+            sienna_mgmt::msg::Handle::SetViewingKey { key: key(address) }
+        ).unwrap();
+    }
     deps
 }
 
+/// The viewing key that `harness` sets for each address
+pub fn key (address: &HumanAddr) -> String {
+    format!("{}'s key", address)
+}
+
 pub fn mock_env (
     height: u64, time: u64, sender: &HumanAddr
 ) -> Env { Env {
@@ -103,16 +115,25 @@ macro_rules! tx {
                 ])
             };
             (claimed: $addr:expr, $amount:expr) => {
+                ok!(claimed: $addr, $amount, to: $addr)
+            };
+            (claimed: $addr:expr, $amount:expr, to: $recipient:expr) => {
                 ok!(messages: [
                     secret_toolkit::snip20::handle::transfer_msg(
-                        $addr.clone(), $amount,
+                        $recipient.clone(), $amount,
                         None, 256, String::new(), HumanAddr::from("token")
                     ).unwrap()
+                ], log: vec![
+                    cosmwasm_std::LogAttribute { key: "claimant".to_string(),  value: $addr.to_string() },
+                    cosmwasm_std::LogAttribute { key: "recipient".to_string(), value: $recipient.to_string() },
+                    cosmwasm_std::LogAttribute { key: "claimed".to_string(),   value: $amount.to_string() },
+                    cosmwasm_std::LogAttribute { key: "time".to_string(),      value: $time.to_string() }
                 ])
             };
         }
         macro_rules! err {
             (auth) => { Err(cosmwasm_std::StdError::Unauthorized { backtrace: None }) };
+            (schedule: $error:expr) => { Err(cosmwasm_std::StdError::from($error)) };
             ($msg:tt) => { Err(cosmwasm_std::StdError::GenericErr {
                 backtrace: None, msg: $msg.to_string()
             }) }
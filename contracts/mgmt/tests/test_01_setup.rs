@@ -3,9 +3,9 @@
 #![allow(non_snake_case)]
 
 #[macro_use] extern crate sienna_mgmt;
-extern crate sienna_schedule; use sienna_schedule::{Schedule, Pool};
+extern crate sienna_schedule; use sienna_schedule::{Schedule, Pool, Account};
 #[macro_use] extern crate kukumba;
-#[macro_use] mod helpers; use helpers::{harness, mock_env};
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
 use cosmwasm_std::HumanAddr;
 
 kukumba! {
@@ -22,7 +22,7 @@ kukumba! {
         }).unwrap(); }
     then "they become admin" { /* admin address is not public */ }
     and "the instance is not launched" { q!(deps; Status   == Status   { launched: None }); }
-    and "the given schedule is set"    { q!(deps; Schedule == Schedule { schedule: s    }); }
+    and "the given schedule is set"    { q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: s    }); }
 
     #[ok_configure_authorized_only]
     given "an instance before launch" { harness!(deps; ADMIN, RECIPIENT, STRANGER); }
@@ -30,20 +30,34 @@ kukumba! {
         let original_schedule = sienna_schedule::Schedule::new(&[Pool::full("original",&[])]);
         tx!(deps; ADMIN, 0, 0; Configure { schedule: original_schedule.clone() } == ok!()); }
     then "the configuration is updated" {
-        q!(deps; Schedule == Schedule { schedule: original_schedule }); }
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: original_schedule }); }
     when "anyone but the admin tries to set a configuration"
     then "that fails" {
         for sender in [&RECIPIENT, &STRANGER].iter() {
             let sender = sender.clone();
             let bad_schedule = Schedule::new(&[Pool::full("malicious",&[])]);
             tx!(deps; sender, 0, 0; Configure { schedule: bad_schedule } == err!(auth));
-            q!(deps; Schedule == Schedule { schedule: original_schedule }); } }
+            q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: original_schedule }); } }
     when "the admin sets the real configuration" {
         let src = include_str!("../../../settings/schedule.json")
         let s: Schedule<HumanAddr> = serde_json::from_str(src).unwrap();
         tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!()); }
     then "the configuration is updated" {
-        q!(deps; Schedule == Schedule { schedule: s.clone() }); }
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: s.clone() }); }
+
+    #[no_configure_invalid]
+    given "an instance before launch" { harness!(deps; ADMIN, ALICE); }
+    when "the admin tries to set a configuration with duplicate names or addresses"
+    then "that fails" {
+        let s = Schedule::new(&[Pool::full("pool",&[]), Pool::full("pool",&[])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() }
+            == err!(schedule: s.err_duplicate_pool::<()>("pool").unwrap_err()));
+        let s = Schedule::new(&[Pool::full("pool",&[
+            Account::immediate("a", &ALICE, 100),
+            Account::immediate("b", &ALICE, 100) ])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() }
+            == err!(schedule: s.err_duplicate_address::<()>("pool", "b", "pool", "a").unwrap_err()));
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: Schedule::new(&[]) }); }
 
     #[no_reconfigure_after_launch]
     given "a launched instance" {
@@ -4,7 +4,7 @@
 
 #[macro_use] extern crate sienna_mgmt;
 #[macro_use] extern crate kukumba;
-#[macro_use] mod helpers; use helpers::{harness, mock_env};
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
 use cosmwasm_std::{HumanAddr, Uint128};
 use sienna_schedule::{Schedule};
 
@@ -16,7 +16,7 @@ kukumba! {
     when "a stranger tries to claim funds"
     then "they are denied" {
         let PRELAUNCH = MGMTError!(PRELAUNCH);
-        tx!(deps; STRANGER, 1, 1; Claim {} == err!(PRELAUNCH)); }
+        tx!(deps; STRANGER, 1, 1; Claim { recipient: None, amount: None } == err!(PRELAUNCH)); }
 
     #[no_claim_as_stranger_after_launch]
     given "the contract is launched" {
@@ -27,7 +27,7 @@ kukumba! {
     when "a stranger tries to claim funds"
     then "they are denied" {
         let NOTHING = MGMTError!(NOTHING); 
-        tx!(deps; STRANGER, 4, 4; Claim {} == err!(NOTHING)); }
+        tx!(deps; STRANGER, 4, 4; Claim { recipient: None, amount: None } == err!(NOTHING)); }
 
     #[ok_claim_as_user_only_after_launch]
     given "a contract with the production schedule" {
@@ -42,7 +42,7 @@ kukumba! {
     and  "anyone tries to claim funds"
     then "they are denied" {
         for user in [&founder_1, &founder_2, &founder_3].iter() {
-            tx!(deps; *user, 1, 1; Claim {} == err!(PRELAUNCH)); } }
+            tx!(deps; *user, 1, 1; Claim { recipient: None, amount: None } == err!(PRELAUNCH)); } }
     when "the contract is launched" {
         let t_launch = 2;
         tx!(deps; ADMIN, 2, t_launch; Launch {} == ok!(launched: s.total)); }
@@ -52,8 +52,8 @@ kukumba! {
             for A in P.accounts.iter() {
                 let address       = A.address.clone();
                 let portion_count = A.portion_count();
-                let portion_size  = A.portion_size();
-                let remainder     = A.remainder();
+                let portion_size  = A.portion_size().unwrap();
+                let remainder     = A.remainder().unwrap();
                 println!("\naccount: {} {} {} {}",
                     &A.name, A.start_at, A.interval, A.duration);
                 println!("amounts: {} = {} + {} * {} + {}",
@@ -64,25 +64,25 @@ kukumba! {
                     "(cliff + portions + remainder) should equal account total");
                 if A.start_at > 0 { //funds are not unlocked before `start_at`
                     q!(deps;
-                        Progress { address: address.clone(), time: A.start_at - 1 } ==
+                        Progress { address: address.clone(), time: A.start_at - 1, key: key(&ADMIN) } ==
                         Progress { unlocked: zero, claimed: zero }); }
                 if A.cliff > zero { // cliff
                     q!(deps;
-                        Progress { address: address.clone(), time: A.start_at + A.interval } ==
+                        Progress { address: address.clone(), time: A.start_at + A.interval, key: key(&ADMIN) } ==
                         Progress { unlocked: A.cliff, claimed: zero });
                 } else { // first portion
                     q!(deps;
-                        Progress { address: address.clone(), time: A.start_at + A.interval } ==
-                        Progress { unlocked: Uint128::from(A.portion_size()), claimed: zero });
+                        Progress { address: address.clone(), time: A.start_at + A.interval, key: key(&ADMIN) } ==
+                        Progress { unlocked: Uint128::from(portion_size), claimed: zero });
                 }
                 q!(deps; // entire amount is unlocked by the end
-                    Progress { address: address.clone(), time: A.start_at + A.duration + A.interval } ==
+                    Progress { address: address.clone(), time: A.start_at + A.duration + A.interval, key: key(&ADMIN) } ==
                     Progress { unlocked: A.amount, claimed: zero }); } } }
     and "by the end of the contract everyone will have unlocked exactly their assigned amount" {
         for P in s.pools.iter() {
             for A in P.accounts.iter() {
                 let t = A.end() + A.interval;
                 tx!(deps; A.address, t / 5, t;
-                    Claim {} == ok!(claimed: A.address, A.amount)); } } }
+                    Claim { recipient: None, amount: None } == ok!(claimed: A.address, A.amount)); } } }
 
 }
@@ -6,9 +6,9 @@
 
 extern crate sienna_mgmt;
 #[macro_use] extern crate kukumba;
-#[macro_use] mod helpers; use helpers::{harness, mock_env};
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
 use cosmwasm_std::{HumanAddr, Uint128};
-use sienna_schedule::{Schedule, Pool, Account};
+use sienna_schedule::{Schedule, Pool, Account, ScheduleError};
 
 kukumba! {
 
@@ -22,7 +22,7 @@ kukumba! {
         let a = Account::immediate("account", &HumanAddr::from("account"), 500);
         tx!(deps; STRANGER, 1, 1; AddAccount { pool_name: "pool".to_string(), account: a }
             == err!(auth));
-        q!(deps; Schedule == Schedule { schedule: original_schedule }); }
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: original_schedule }); }
 
     #[no_add_user_to_missing_pool]
     given "an instance" { harness!(deps; ADMIN); }
@@ -33,8 +33,8 @@ kukumba! {
     then "that fails" {
         let a = Account::immediate("account", &HumanAddr::from("account"), 500);
         tx!(deps; ADMIN, 1, 1; AddAccount { pool_name: "missing".to_string(), account: a }
-            == err!("schedule: pool missing not found"));
-        q!(deps; Schedule == Schedule { schedule: original_schedule }); }
+            == err!(schedule: ScheduleError::PoolNotFound { pool: "missing".to_string() }));
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: original_schedule }); }
 
     #[ok_add_user_to_pool_before_launch]
     given "an instance" { harness!(deps; ADMIN); }
@@ -48,7 +48,7 @@ kukumba! {
         updated_schedule.add_account("pool", a.clone()).unwrap();
         tx!(deps; ADMIN, 1, 1; AddAccount { pool_name: "pool".to_string(), account: a.clone() }
             == ok!());
-        q!(deps; Schedule == Schedule { schedule: updated_schedule }); }
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: updated_schedule }); }
 
     #[no_add_user_to_full_pool_before_launch]
     given "an instance" { harness!(deps; ADMIN); }
@@ -60,7 +60,11 @@ kukumba! {
     then "that fails" {
         let a = Account::immediate("account", &HumanAddr::from("account"), 1001);
         tx!(deps; ADMIN, 1, 1; AddAccount { pool_name: "pool".to_string(), account: a }
-            == err!("pool pool: account (1001) > unallocated funds in pool (1000)")); }
+            == err!(schedule: ScheduleError::AccountTooBig {
+                pool:        "pool".to_string(),
+                account:     "account".to_string(),
+                amount:      Uint128::from(1001u128),
+                unallocated: Uint128::from(1000u128) })); }
 
     #[no_unauthorized_mutate_after_launch]
     given "a launched instance"
@@ -90,8 +94,8 @@ kukumba! {
         updated_schedule.add_account("pool", a.clone()).unwrap();
         tx!(deps; ADMIN, 1, 1; AddAccount { pool_name: "pool".to_string(), account: a.clone() }
             == ok!());
-        q!(deps; Schedule == Schedule { schedule: updated_schedule });
-        q!(deps; Progress { address: a.address, time: 0 }
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: updated_schedule });
+        q!(deps; Progress { address: a.address, time: 0, key: key(&ADMIN) }
             == Progress { unlocked: cosmwasm_std::Uint128::from(500u128) }); }
 
     #[no_add_user_to_full_pool_after_launch]
@@ -106,6 +110,10 @@ kukumba! {
     then "that fails" {
         let a = Account::immediate("account", &HumanAddr::from("account"), 1001);
         tx!(deps; ADMIN, 1, 1; AddAccount { pool_name: "pool".to_string(), account: a }
-            == err!("pool pool: account (1001) > unallocated funds in pool (1000)")); }
+            == err!(schedule: ScheduleError::AccountTooBig {
+                pool:        "pool".to_string(),
+                account:     "account".to_string(),
+                amount:      Uint128::from(1001u128),
+                unallocated: Uint128::from(1000u128) })); }
 
 }
@@ -0,0 +1,72 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128, LogAttribute};
+use sienna_schedule::{Schedule, Pool, Account, ScheduleError};
+
+kukumba! {
+
+    #[no_revoke_before_launch]
+    given "an instance that is not launched" {
+        harness!(deps; ADMIN, ALICE);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!()); }
+    when "the admin tries to revoke an account"
+    then "that fails" {
+        let PRELAUNCH = MGMTError!(PRELAUNCH);
+        tx!(deps; ADMIN, 1, 1; Revoke { pool: "pool".to_string(), account: "alice".to_string() }
+            == err!(PRELAUNCH)); }
+
+    #[ok_revoke]
+    given "a launched instance with a vesting account" {
+        harness!(deps; ADMIN, ALICE, BOB, STRANGER);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total)); }
+    when "anyone but the admin tries to revoke the account"
+    then "that fails" {
+        for sender in [&ALICE, &STRANGER].iter() {
+            let sender = sender.clone();
+            tx!(deps; sender, 2, 26; Revoke { pool: "pool".to_string(), account: "alice".to_string() }
+                == err!(auth)); } }
+    when "the admin revokes the account"
+    then "what has vested so far remains unlocked"
+    and  "nothing more vests afterwards" {
+        tx!(deps; ADMIN, 2, 26; Revoke { pool: "pool".to_string(), account: "alice".to_string() }
+            == ok!(messages: [], log: vec![
+                LogAttribute { key: "revoked".to_string(), value: "25".to_string() } ]));
+        q!(deps; Progress { address: ALICE.clone(), time: 1000, key: key(&ALICE) }
+            == Progress { unlocked: Uint128::from(30u128) }); }
+    and  "the account can't be revoked twice" {
+        tx!(deps; ADMIN, 3, 30; Revoke { pool: "pool".to_string(), account: "alice".to_string() }
+            == err!(schedule: ScheduleError::AlreadyRevoked { account: "alice".to_string() })); }
+    when "the recipient claims"
+    then "they receive only what vested before the revocation" {
+        tx!(deps; ALICE, 4, 1000; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(30u128)));
+        let NOTHING = MGMTError!(NOTHING);
+        tx!(deps; ALICE, 5, 2000; Claim { recipient: None, amount: None } == err!(NOTHING)); }
+    when "the admin adds an account with the unvested funds"
+    then "the pool accepts it" {
+        tx!(deps; ADMIN, 6, 2000; AddAccount {
+            pool_name: "pool".to_string(),
+            account: Account::immediate("bob", &BOB, 71) }
+            == err!(schedule: ScheduleError::AccountTooBig {
+                pool:        "pool".to_string(),
+                account:     "bob".to_string(),
+                amount:      Uint128::from(71u128),
+                unallocated: Uint128::from(70u128) }));
+        tx!(deps; ADMIN, 6, 2000; AddAccount {
+            pool_name: "pool".to_string(),
+            account: Account::immediate("bob", &BOB, 70) }
+            == ok!());
+        q!(deps; Progress { address: BOB.clone(), time: 2000, key: key(&BOB) }
+            == Progress { unlocked: Uint128::from(70u128) }); }
+
+}
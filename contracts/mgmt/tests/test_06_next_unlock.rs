@@ -0,0 +1,62 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account};
+
+kukumba! {
+
+    #[no_next_unlock_before_launch]
+    given "an instance that is not launched" {
+        harness!(deps; ADMIN, ALICE);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!()); }
+    when "someone asks when the next unlock is"
+    then "they get an error" {
+        let PRELAUNCH = MGMTError!(PRELAUNCH);
+        q!(deps; NextUnlock { address: ALICE.clone(), time: 0, key: key(&ALICE) }
+            == Error { msg: PRELAUNCH }); }
+
+    #[ok_next_unlock]
+    given "a launched instance with a vesting account" {
+        harness!(deps; ADMIN, ALICE, STRANGER);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total)); }
+    when "the recipient asks when the next unlock is"
+    then "they get the time and amount of the next portion, the end, and what's still locked" {
+        q!(deps; NextUnlock { address: ALICE.clone(), time: 1, key: key(&ALICE) }
+            == NextUnlock {
+                next_time:   Some(11),
+                next_amount: Uint128::from(10u128),
+                final_time:  Some(91),
+                locked:      Uint128::from(90u128) });
+        q!(deps; NextUnlock { address: ALICE.clone(), time: 55, key: key(&ALICE) }
+            == NextUnlock {
+                next_time:   Some(61),
+                next_amount: Uint128::from(10u128),
+                final_time:  Some(91),
+                locked:      Uint128::from(40u128) }); }
+    and  "after the end there is nothing left" {
+        q!(deps; NextUnlock { address: ALICE.clone(), time: 1000, key: key(&ALICE) }
+            == NextUnlock {
+                next_time:   None,
+                next_amount: Uint128::zero(),
+                final_time:  Some(91),
+                locked:      Uint128::zero() }); }
+    when "a stranger asks"
+    then "there's nothing for them" {
+        q!(deps; NextUnlock { address: STRANGER.clone(), time: 1, key: key(&STRANGER) }
+            == NextUnlock {
+                next_time:   None,
+                next_amount: Uint128::zero(),
+                final_time:  None,
+                locked:      Uint128::zero() }); }
+
+}
@@ -0,0 +1,57 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account};
+use sienna_mgmt::AccountProgress;
+
+fn progress (pool: &str, account: &str, unlocked: u128, claimed: u128, remaining: u128) -> AccountProgress {
+    AccountProgress {
+        pool:      pool.to_string(),
+        account:   account.to_string(),
+        unlocked:  Uint128::from(unlocked),
+        claimed:   Uint128::from(claimed),
+        remaining: Uint128::from(remaining)
+    }
+}
+
+kukumba! {
+
+    #[ok_breakdown]
+    given "a launched instance where one address has accounts in two pools" {
+        harness!(deps; ADMIN, ALICE);
+        let mut investor = Account::periodic("investor", &ALICE, 100, 0, 0, 10, 100);
+        let mut advisor  = Account::immediate("advisor", &ALICE, 50);
+        investor.shared_address = true;
+        advisor.shared_address  = true;
+        let s = Schedule::new(&[
+            Pool::full("investors", &[investor]),
+            Pool::full("advisors",  &[advisor])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total)); }
+    when "the recipient asks for a breakdown"
+    then "they see how much each account has unlocked" {
+        q!(deps; Breakdown { address: ALICE.clone(), time: 26, key: key(&ALICE) }
+            == Breakdown { accounts: vec![
+                progress("investors", "investor", 30, 0, 100),
+                progress("advisors",  "advisor",  50, 0,  50)] });
+        q!(deps; Progress { address: ALICE.clone(), time: 26, key: key(&ALICE) }
+            == Progress { unlocked: Uint128::from(80u128) }); }
+    when "the recipient claims"
+    then "the claimed amount is attributed to the accounts in order" {
+        tx!(deps; ALICE, 2, 26; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(80u128)));
+        q!(deps; Breakdown { address: ALICE.clone(), time: 26, key: key(&ALICE) }
+            == Breakdown { accounts: vec![
+                progress("investors", "investor", 30, 30, 70),
+                progress("advisors",  "advisor",  50, 50,  0)] });
+        q!(deps; Breakdown { address: ALICE.clone(), time: 46, key: key(&ALICE) }
+            == Breakdown { accounts: vec![
+                progress("investors", "investor", 50, 30, 70),
+                progress("advisors",  "advisor",  50, 50,  0)] }); }
+
+}
@@ -0,0 +1,75 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{Uint128, StdError, from_binary};
+use sienna_schedule::{Schedule, Pool, Account};
+use sienna_mgmt::msg::{Query, Handle, Response};
+
+kukumba! {
+
+    #[ok_viewing_keys]
+    given "a launched instance with a vesting account" {
+        harness!(deps; ADMIN, ALICE, BOB, STRANGER);
+        let alice = Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            alice.clone(), Account::immediate("bob", &BOB, 50)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total));
+        let UNAUTHORIZED = Err(StdError::Unauthorized { backtrace: None }); }
+    when "someone queries the recipient's progress without the right key"
+    then "they are denied" {
+        for wrong_key in [key(&STRANGER), String::new(), "wrong".to_string()].iter() {
+            assert_eq!(sienna_mgmt::query(&deps, Query::Progress {
+                address: ALICE.clone(), time: 1000, key: wrong_key.clone()
+            }), UNAUTHORIZED);
+            assert_eq!(sienna_mgmt::query(&deps, Query::NextUnlock {
+                address: ALICE.clone(), time: 1000, key: wrong_key.clone()
+            }), UNAUTHORIZED);
+            assert_eq!(sienna_mgmt::query(&deps, Query::Breakdown {
+                address: ALICE.clone(), time: 1000, key: wrong_key.clone()
+            }), UNAUTHORIZED); } }
+    when "the recipient or the admin query it with their key"
+    then "they can see it" {
+        q!(deps; Progress { address: ALICE.clone(), time: 1000, key: key(&ALICE) }
+            == Progress { unlocked: Uint128::from(100u128) });
+        q!(deps; Progress { address: ALICE.clone(), time: 1000, key: key(&ADMIN) }
+            == Progress { unlocked: Uint128::from(100u128) }); }
+    when "anyone but the admin queries the schedule or the history"
+    then "they are denied" {
+        assert_eq!(sienna_mgmt::query(&deps, Query::Schedule { key: key(&ALICE), address: None }),
+            UNAUTHORIZED);
+        assert_eq!(sienna_mgmt::query(&deps, Query::History { key: key(&ALICE), start: None, limit: None }),
+            UNAUTHORIZED);
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: s.clone() }); }
+    when "the recipient queries the schedule for their own address"
+    then "they see only their own accounts"
+    and "noone else can see them without the right key" {
+        let own = Schedule { total: s.total, pools: vec![
+            Pool { accounts: vec![alice.clone()], ..s.pools[0].clone() }] };
+        q!(deps; Schedule { key: key(&ALICE), address: Some(ALICE.clone()) }
+            == Schedule { schedule: own.clone() });
+        q!(deps; Schedule { key: key(&ADMIN), address: Some(ALICE.clone()) }
+            == Schedule { schedule: own.clone() });
+        assert_eq!(sienna_mgmt::query(&deps, Query::Schedule {
+            key: key(&STRANGER), address: Some(ALICE.clone())
+        }), UNAUTHORIZED); }
+    when "the recipient generates a new key"
+    then "only the new key works" {
+        let response = sienna_mgmt::handle(&mut deps, mock_env(2, 2, &ALICE),
+            Handle::CreateViewingKey { entropy: "entropy".to_string() }).unwrap();
+        let new_key = match from_binary(&response.data.unwrap()).unwrap() {
+            Response::ViewingKey { key } => key,
+            _ => panic!("CreateViewingKey did not return a key")
+        };
+        assert!(new_key.starts_with("api_key_"));
+        q!(deps; Progress { address: ALICE.clone(), time: 1000, key: new_key.clone() }
+            == Progress { unlocked: Uint128::from(100u128) });
+        assert_eq!(sienna_mgmt::query(&deps, Query::Progress {
+            address: ALICE.clone(), time: 1000, key: key(&ALICE)
+        }), UNAUTHORIZED); }
+
+}
@@ -0,0 +1,50 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account};
+use sienna_mgmt::LinearMap;
+
+kukumba! {
+
+    #[ok_history]
+    given "a launched instance where three recipients have claimed" {
+        harness!(deps; ADMIN, ALICE, BOB, CAROL, STRANGER);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::immediate("alice", &ALICE, 10),
+            Account::immediate("bob",   &BOB,   20),
+            Account::immediate("carol", &CAROL, 30)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total));
+        tx!(deps; BOB,   2, 2; Claim { recipient: None, amount: None }
+            == ok!(claimed: BOB,   Uint128::from(20u128)));
+        tx!(deps; ALICE, 3, 3; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(10u128)));
+        tx!(deps; CAROL, 4, 4; Claim { recipient: None, amount: None }
+            == ok!(claimed: CAROL, Uint128::from(30u128))); }
+    when "the admin pages through the history"
+    then "they get the entries in order of first claim, and the total count" {
+        q!(deps; History { key: key(&ADMIN), start: None, limit: Some(2) }
+            == History {
+                history: LinearMap(vec![
+                    (BOB.clone(),   Uint128::from(20u128)),
+                    (ALICE.clone(), Uint128::from(10u128))]),
+                total: 3 });
+        q!(deps; History { key: key(&ADMIN), start: Some(2), limit: Some(2) }
+            == History {
+                history: LinearMap(vec![(CAROL.clone(), Uint128::from(30u128))]),
+                total: 3 });
+        q!(deps; History { key: key(&ADMIN), start: Some(3), limit: None }
+            == History { history: LinearMap(vec![]), total: 3 }); }
+    when "a recipient looks up how much they have claimed"
+    then "they get just their own entry" {
+        q!(deps; Claimed { address: ALICE.clone(), key: key(&ALICE) }
+            == Claimed { address: ALICE.clone(), claimed: Uint128::from(10u128) });
+        q!(deps; Claimed { address: STRANGER.clone(), key: key(&STRANGER) }
+            == Claimed { claimed: Uint128::zero() }); }
+
+}
@@ -0,0 +1,55 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128, StdError};
+use sienna_schedule::{Schedule, Pool, Account};
+use sienna_mgmt::{ClaimEntry, msg::Query};
+
+fn entry (address: &HumanAddr, time: u64, height: u64, amount: u128) -> ClaimEntry<HumanAddr> {
+    ClaimEntry {
+        address:   address.clone(),
+        recipient: address.clone(),
+        time, height,
+        amount:    Uint128::from(amount)
+    }
+}
+
+kukumba! {
+
+    #[ok_claims]
+    given "a launched instance where recipients have claimed several times" {
+        harness!(deps; ADMIN, ALICE, BOB);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100),
+            Account::immediate("bob", &BOB, 20)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total));
+        tx!(deps; ALICE, 2, 11; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(20u128)));
+        tx!(deps; BOB,   3, 12; Claim { recipient: None, amount: None }
+            == ok!(claimed: BOB,   Uint128::from(20u128)));
+        tx!(deps; ALICE, 4, 31; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(20u128))); }
+    when "the admin pages through the claim log"
+    then "they get every claim in order, with its time, height and amount" {
+        q!(deps; Claims { key: key(&ADMIN), start: None, limit: Some(2) }
+            == Claims {
+                claims: vec![
+                    entry(&ALICE, 11, 2, 20),
+                    entry(&BOB,   12, 3, 20)],
+                total: 3 });
+        q!(deps; Claims { key: key(&ADMIN), start: Some(2), limit: Some(2) }
+            == Claims { claims: vec![entry(&ALICE, 31, 4, 20)], total: 3 });
+        q!(deps; Claims { key: key(&ADMIN), start: Some(3), limit: None }
+            == Claims { claims: vec![], total: 3 }); }
+    when "a recipient tries to read the claim log"
+    then "they are denied" {
+        assert_eq!(sienna_mgmt::query(&deps, Query::Claims {
+            key: key(&ALICE), start: None, limit: None
+        }), Err(StdError::Unauthorized { backtrace: None })); }
+
+}
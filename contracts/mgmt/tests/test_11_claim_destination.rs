@@ -0,0 +1,52 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account};
+use sienna_mgmt::ClaimEntry;
+
+kukumba! {
+
+    #[ok_claim_to_recipient]
+    given "a launched instance with a vesting account" {
+        harness!(deps; ADMIN, ALICE, COLD_WALLET, CUSTODIAN);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total)); }
+    when "the recipient claims specifying another address"
+    then "the funds are sent to that address" {
+        tx!(deps; ALICE, 2, 11; Claim { recipient: Some(COLD_WALLET.clone()), amount: None }
+            == ok!(claimed: ALICE, Uint128::from(20u128), to: COLD_WALLET)); }
+    and  "the claim is counted towards the recipient, not the address the funds went to" {
+        q!(deps; Claimed { address: ALICE.clone(), key: key(&ALICE) }
+            == Claimed { claimed: Uint128::from(20u128) });
+        q!(deps; Claimed { address: COLD_WALLET.clone(), key: key(&COLD_WALLET) }
+            == Claimed { claimed: Uint128::zero() });
+        q!(deps; Claims { key: key(&ADMIN), start: None, limit: None }
+            == Claims { claims: vec![ClaimEntry {
+                address:   ALICE.clone(),
+                recipient: COLD_WALLET.clone(),
+                time:      11,
+                height:    2,
+                amount:    Uint128::from(20u128) }] }); }
+    when "the recipient sets a default destination"
+    then "claims that don't specify a recipient go there" {
+        tx!(deps; ALICE, 3, 21; SetClaimDestination { destination: Some(CUSTODIAN.clone()) }
+            == ok!());
+        tx!(deps; ALICE, 4, 31; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(20u128), to: CUSTODIAN)); }
+    and  "claims that specify a recipient still go to it" {
+        tx!(deps; ALICE, 5, 41; Claim { recipient: Some(COLD_WALLET.clone()), amount: None }
+            == ok!(claimed: ALICE, Uint128::from(10u128), to: COLD_WALLET)); }
+    when "the recipient clears the default destination"
+    then "claims go to the recipient again" {
+        tx!(deps; ALICE, 6, 51; SetClaimDestination { destination: None } == ok!());
+        tx!(deps; ALICE, 7, 61; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(20u128))); }
+
+}
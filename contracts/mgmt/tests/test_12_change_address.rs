@@ -0,0 +1,82 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128, LogAttribute};
+use sienna_schedule::{Schedule, Pool, Account};
+
+fn changed (address: &HumanAddr, new_address: &HumanAddr) -> Vec<LogAttribute> {
+    vec![
+        LogAttribute { key: "address".to_string(),     value: address.to_string() },
+        LogAttribute { key: "new_address".to_string(), value: new_address.to_string() },
+    ]
+}
+
+kukumba! {
+
+    #[ok_propose_and_accept_address]
+    given "a launched instance where a recipient has claimed some funds" {
+        harness!(deps; ADMIN, ALICE, ALICE_NEW, MALLORY);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total));
+        tx!(deps; ALICE, 2, 11; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(20u128)));
+        let NO_PROPOSAL = MGMTError!(NO_PROPOSAL);
+        let NOTHING     = MGMTError!(NOTHING); }
+    when "nobody has proposed a change of address to someone"
+    then "they can't take over anyone's accounts" {
+        tx!(deps; MALLORY, 3, 12; AcceptAddress { address: ALICE.clone() } == err!(NO_PROPOSAL)); }
+    when "the recipient proposes a new address"
+    then "only that address can accept it" {
+        tx!(deps; ALICE, 4, 13; ProposeAddress { new_address: ALICE_NEW.clone() } == ok!());
+        tx!(deps; MALLORY, 5, 14; AcceptAddress { address: ALICE.clone() } == err!(NO_PROPOSAL));
+        q!(deps; Claimed { address: ALICE.clone(), key: key(&ALICE) }
+            == Claimed { claimed: Uint128::from(20u128) }); }
+    when "the new address accepts"
+    then "the accounts and what has been claimed from them move to it" {
+        tx!(deps; ALICE_NEW, 6, 15; AcceptAddress { address: ALICE.clone() }
+            == ok!(messages: [], log: changed(&ALICE, &ALICE_NEW)));
+        q!(deps; Progress { address: ALICE_NEW.clone(), time: 31, key: key(&ALICE_NEW) }
+            == Progress { unlocked: Uint128::from(40u128), claimed: Uint128::from(20u128) });
+        q!(deps; Progress { address: ALICE.clone(), time: 31, key: key(&ALICE) }
+            == Progress { unlocked: Uint128::zero(), claimed: Uint128::zero() });
+        tx!(deps; ALICE, 7, 31; Claim { recipient: None, amount: None } == err!(NOTHING));
+        tx!(deps; ALICE_NEW, 8, 31; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE_NEW, Uint128::from(20u128))); }
+    and  "the proposal can't be accepted again" {
+        tx!(deps; ALICE_NEW, 9, 32; AcceptAddress { address: ALICE.clone() } == err!(NO_PROPOSAL)); }
+
+    #[ok_admin_change_address]
+    given "a launched instance with a vesting account" {
+        harness!(deps; ADMIN, ALICE, ALICE_NEW, BOB);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100),
+            Account::immediate("bob", &BOB, 10)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total));
+        let NOTHING = MGMTError!(NOTHING); }
+    when "someone other than the admin tries to change an address"
+    then "they are denied" {
+        tx!(deps; ALICE, 2, 2; ChangeAddress {
+            address: ALICE.clone(), new_address: ALICE_NEW.clone() } == err!(auth)); }
+    when "the admin moves an account to an address that already has one"
+    then "nothing changes" {
+        tx!(deps; ADMIN, 3, 3; ChangeAddress {
+            address: ALICE.clone(), new_address: BOB.clone()
+        } == err!(schedule: sienna_schedule::ScheduleError::AddressInUse {
+            pool: "pool".to_string(), account: "bob".to_string() })); }
+    when "the admin moves the account to a new address"
+    then "the new address can claim from it" {
+        tx!(deps; ADMIN, 4, 4; ChangeAddress {
+            address: ALICE.clone(), new_address: ALICE_NEW.clone()
+        } == ok!(messages: [], log: changed(&ALICE, &ALICE_NEW)));
+        tx!(deps; ALICE, 5, 11; Claim { recipient: None, amount: None } == err!(NOTHING));
+        tx!(deps; ALICE_NEW, 6, 11; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE_NEW, Uint128::from(20u128))); }
+
+}
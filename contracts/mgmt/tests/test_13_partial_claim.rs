@@ -0,0 +1,44 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account};
+
+kukumba! {
+
+    #[ok_partial_claim]
+    given "a launched instance with a vesting account" {
+        harness!(deps; ADMIN, ALICE);
+        let s = Schedule::new(&[Pool::full("pool", &[
+            Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100)])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total));
+        let NOTHING = MGMTError!(NOTHING); }
+    when "the recipient claims a specific amount"
+    then "they receive only that amount" {
+        tx!(deps; ALICE, 2, 21; Claim { recipient: None, amount: Some(Uint128::from(5u128)) }
+            == ok!(claimed: ALICE, Uint128::from(5u128)));
+        q!(deps; Progress { address: ALICE.clone(), time: 21, key: key(&ALICE) }
+            == Progress { unlocked: Uint128::from(30u128), claimed: Uint128::from(5u128) }); }
+    and  "they can claim the rest later" {
+        tx!(deps; ALICE, 3, 21; Claim { recipient: None, amount: Some(Uint128::from(20u128)) }
+            == ok!(claimed: ALICE, Uint128::from(20u128)));
+        q!(deps; Claimed { address: ALICE.clone(), key: key(&ALICE) }
+            == Claimed { claimed: Uint128::from(25u128) }); }
+    when "the recipient asks for more than is claimable"
+    then "they receive what is claimable" {
+        tx!(deps; ALICE, 4, 21; Claim { recipient: None, amount: Some(Uint128::from(1000u128)) }
+            == ok!(claimed: ALICE, Uint128::from(5u128)));
+        tx!(deps; ALICE, 5, 21; Claim { recipient: None, amount: None } == err!(NOTHING)); }
+    when "the recipient asks for nothing"
+    then "nothing is claimed" {
+        tx!(deps; ALICE, 6, 31; Claim { recipient: None, amount: Some(Uint128::zero()) }
+            == err!(NOTHING));
+        tx!(deps; ALICE, 7, 31; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(10u128))); }
+
+}
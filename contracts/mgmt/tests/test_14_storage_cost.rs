@@ -0,0 +1,128 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::mock_env;
+use std::cell::Cell;
+use cosmwasm_std::{
+    HumanAddr, Uint128, Extern, MemoryStorage, ReadonlyStorage, Storage, to_vec,
+    testing::{MockApi, MockQuerier}
+};
+use sienna_schedule::{Schedule, Pool, Account};
+
+/// Storage that counts how many keys are accessed and how many bytes are written,
+/// as a rough proxy for gas.
+struct CountingStorage {
+    storage:  MemoryStorage,
+    accessed: Cell<usize>,
+    written:  Cell<usize>
+}
+impl CountingStorage {
+    fn reset (&self) {
+        self.accessed.set(0);
+        self.written.set(0);
+    }
+    fn count (&self, written: usize) {
+        self.accessed.set(self.accessed.get() + 1);
+        self.written.set(self.written.get() + written)
+    }
+}
+impl ReadonlyStorage for CountingStorage {
+    fn get (&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.count(0);
+        self.storage.get(key)
+    }
+}
+impl Storage for CountingStorage {
+    fn set (&mut self, key: &[u8], value: &[u8]) {
+        self.count(key.len() + value.len());
+        self.storage.set(key, value)
+    }
+    fn remove (&mut self, key: &[u8]) {
+        self.count(key.len());
+        self.storage.remove(key)
+    }
+}
+
+type ExternCounting = Extern<CountingStorage, MockApi, MockQuerier>;
+
+/// A launched instance with `n` accounts, and a partial pool with room for more
+fn launched (n: usize) -> (ExternCounting, Schedule<HumanAddr>) {
+    let ADMIN = HumanAddr::from("ADMIN");
+    let mut deps = Extern {
+        storage: CountingStorage {
+            storage:  MemoryStorage::new(),
+            accessed: Cell::new(0),
+            written:  Cell::new(0)
+        },
+        api:     MockApi::new(45),
+        querier: MockQuerier::new(&[])
+    };
+    sienna_mgmt::init(&mut deps, mock_env(0, 0, &ADMIN), sienna_mgmt::msg::Init {
+        history:  None,
+        schedule: Schedule::new(&[]),
+        token:    (HumanAddr::from("token"), String::new()),
+    }).unwrap();
+    let accounts: Vec<_> = (0..n).map(|i| Account::immediate(
+        &format!("account{:04}", i), &recipient(i), 1000
+    )).collect();
+    let s = Schedule::new(&[
+        Pool::full("pool", &accounts),
+        Pool::partial("reserve", 1000, &[])]);
+    tx!(deps; ADMIN, 1, 1; Configure { schedule: s.clone() } == ok!());
+    tx!(deps; ADMIN, 2, 2; Launch {} == ok!(launched: s.total));
+    (deps, s)
+}
+
+fn recipient (i: usize) -> HumanAddr {
+    HumanAddr::from(format!("RECIPIENT{:04}", i))
+}
+
+/// What some transactions cost (see `costs`)
+struct Costs {
+    /// How many keys a claim accesses
+    claim_accessed: usize,
+    /// How many bytes a claim writes
+    claim_written:  usize,
+    /// How many bytes adding an account writes
+    add_written:    usize
+}
+
+/// Have the first recipient claim, then have the admin add an account to the partial pool
+fn costs (mut deps: ExternCounting) -> Costs {
+    let ADMIN     = HumanAddr::from("ADMIN");
+    let RECIPIENT = recipient(0);
+    deps.storage.reset();
+    tx!(deps; RECIPIENT, 3, 3; Claim { recipient: None, amount: None }
+        == ok!(claimed: RECIPIENT, Uint128::from(1000u128)));
+    let (claim_accessed, claim_written) = (deps.storage.accessed.get(), deps.storage.written.get());
+    deps.storage.reset();
+    tx!(deps; ADMIN, 4, 4; AddAccount {
+        pool_name: "reserve".to_string(),
+        account:   Account::immediate("new", &HumanAddr::from("NEW"), 100)
+    } == ok!());
+    Costs { claim_accessed, claim_written, add_written: deps.storage.written.get() }
+}
+
+kukumba! {
+
+    #[cost_does_not_grow_with_schedule]
+    given "a launched instance with 1 account and one with 200 accounts" {
+        let (small, _) = launched(1);
+        let (large, s) = launched(200); }
+    when "a recipient claims from each, and the admin adds an account to each"
+    then "both claims access the same keys and write the same amount of data" {
+        let small = costs(small);
+        let large = costs(large);
+        assert_eq!(large.claim_accessed, small.claim_accessed);
+        assert_eq!(large.claim_written,  small.claim_written); }
+    and  "that is much less than it would take to save the whole schedule" {
+        let schedule_size = to_vec(&s).unwrap().len();
+        assert!(large.claim_written * 10 < schedule_size); }
+    and  "both additions write the same amount of data" {
+        assert_eq!(large.add_written, small.add_written);
+        assert!(large.add_written * 10 < schedule_size); }
+
+}
@@ -0,0 +1,93 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account, ScheduleError};
+
+kukumba! {
+
+    #[ok_chunked_configure]
+    given "an instance" {
+        harness!(deps; ADMIN, ALICE, BOB, CAROL, STRANGER);
+        let NOT_STAGED = MGMTError!(NOT_STAGED);
+        let UNDERWAY   = MGMTError!(UNDERWAY);
+        let alice = Account::periodic("alice", &ALICE, 100, 0, 0, 10, 100);
+        let bob   = Account::immediate("bob",   &BOB,   100);
+        let carol = Account::immediate("carol", &CAROL, 100);
+        let s = Schedule::new(&[
+            Pool::full("investors", &[alice.clone()]),
+            Pool::full("advisors",  &[bob.clone(), carol.clone()])]); }
+    when "someone other than the admin tries to upload a schedule"
+    then "they are denied" {
+        tx!(deps; STRANGER, 0, 0; BeginConfigure { total: s.total } == err!(auth)); }
+    when "the admin adds to an upload that hasn't begun"
+    then "that fails" {
+        tx!(deps; ADMIN, 1, 1; AppendPool { pool: Pool::full("investors", &[alice.clone()]) }
+            == err!(NOT_STAGED));
+        tx!(deps; ADMIN, 2, 2; FinalizeConfigure {} == err!(NOT_STAGED)); }
+    when "the admin uploads a schedule a few pools and accounts at a time"
+    then "it doesn't take effect until it's finalized" {
+        tx!(deps; ADMIN, 3, 3; BeginConfigure { total: s.total } == ok!());
+        tx!(deps; ADMIN, 4, 4; AppendPool { pool: Pool::full("investors", &[alice.clone()]) }
+            == ok!());
+        tx!(deps; ADMIN, 5, 5; AppendPool { pool: Pool {
+            accounts: vec![bob.clone()], ..s.pools[1].clone()
+        } } == ok!());
+        tx!(deps; ADMIN, 6, 6; AppendAccounts {
+            pool_name: "missing".to_string(), accounts: vec![carol.clone()]
+        } == err!(schedule: ScheduleError::PoolNotFound { pool: "missing".to_string() }));
+        tx!(deps; ADMIN, 7, 7; AppendAccounts {
+            pool_name: "advisors".to_string(), accounts: vec![carol.clone()]
+        } == ok!());
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: Schedule::new(&[]) }); }
+    and  "then it replaces the current schedule" {
+        tx!(deps; ADMIN, 8, 8; FinalizeConfigure {} == ok!());
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: s.clone() });
+        tx!(deps; ADMIN, 9, 9; FinalizeConfigure {} == err!(NOT_STAGED)); }
+    when "the uploaded schedule is invalid"
+    then "finalizing fails, and the upload can be completed" {
+        let total = Uint128::from(1000u128);
+        tx!(deps; ADMIN, 10, 10; BeginConfigure { total } == ok!());
+        tx!(deps; ADMIN, 11, 11; AppendPool { pool: Pool::full("investors", &[alice.clone()]) }
+            == ok!());
+        tx!(deps; ADMIN, 12, 12; FinalizeConfigure {}
+            == err!(schedule: ScheduleError::TotalMismatch {
+                subtotal: Uint128::from(100u128), total }));
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: s.clone() });
+        tx!(deps; ADMIN, 13, 13; AppendPool { pool: Pool::partial("reserve", 900, &[]) }
+            == ok!());
+        tx!(deps; ADMIN, 14, 14; FinalizeConfigure {} == ok!());
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: Schedule {
+            total, pools: vec![
+                Pool::full("investors", &[alice.clone()]),
+                Pool::partial("reserve", 900, &[])] } }); }
+    when "the accounts appended to a full pool don't add up to its total"
+    then "finalizing fails" {
+        tx!(deps; ADMIN, 15, 15; BeginConfigure { total: s.total } == ok!());
+        tx!(deps; ADMIN, 16, 16; AppendPool { pool: Pool::full("investors", &[alice.clone()]) }
+            == ok!());
+        tx!(deps; ADMIN, 17, 17; AppendPool { pool: Pool {
+            accounts: vec![bob.clone()], ..s.pools[1].clone()
+        } } == ok!());
+        tx!(deps; ADMIN, 18, 18; FinalizeConfigure {}
+            == err!(schedule: ScheduleError::PoolTotalMismatch {
+                pool:     "advisors".to_string(),
+                subtotal: Uint128::from(100u128),
+                total:    Uint128::from(200u128) })); }
+    when "the admin aborts an upload"
+    then "it can't be finalized" {
+        tx!(deps; ADMIN, 19, 19; BeginConfigure { total: s.total } == ok!());
+        tx!(deps; ADMIN, 20, 20; AppendPool { pool: Pool::full("investors", &[alice.clone()]) }
+            == ok!());
+        tx!(deps; ADMIN, 21, 21; AbortConfigure {} == ok!());
+        tx!(deps; ADMIN, 22, 22; FinalizeConfigure {} == err!(NOT_STAGED)); }
+    when "the vesting has been launched"
+    then "no more schedules can be uploaded" {
+        tx!(deps; ADMIN, 23, 23; Launch {} == ok!(launched: Uint128::from(1000u128)));
+        tx!(deps; ADMIN, 24, 24; BeginConfigure { total: s.total } == err!(UNDERWAY)); }
+
+}
@@ -0,0 +1,51 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account, ScheduleError};
+
+kukumba! {
+
+    #[ok_add_accounts]
+    given "an instance with two partial pools" {
+        harness!(deps; ADMIN, ALICE, BOB, CAROL, STRANGER);
+        let s = Schedule::new(&[
+            Pool::partial("investors", 300, &[]),
+            Pool::partial("advisors",  100, &[])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        let alice = Account::immediate("alice", &ALICE, 100);
+        let bob   = Account::periodic("bob", &BOB, 200, 0, 0, 10, 100);
+        let carol = Account::immediate("carol", &CAROL, 100); }
+    when "someone other than the admin tries to add accounts"
+    then "they are denied" {
+        tx!(deps; STRANGER, 1, 1; AddAccounts { accounts: vec![
+            ("investors".to_string(), alice.clone())] } == err!(auth)); }
+    when "the admin adds a batch in which one account doesn't fit"
+    then "none of the accounts are added" {
+        let too_big = Account::immediate("carol", &CAROL, 101);
+        tx!(deps; ADMIN, 2, 2; AddAccounts { accounts: vec![
+            ("investors".to_string(), alice.clone()),
+            ("advisors".to_string(),  too_big.clone())]
+        } == err!(schedule: ScheduleError::AccountTooBig {
+            pool:        "advisors".to_string(),
+            account:     "carol".to_string(),
+            amount:      Uint128::from(101u128),
+            unallocated: Uint128::from(100u128) }));
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: s.clone() }); }
+    when "the admin adds a batch of valid accounts"
+    then "they are all added" {
+        tx!(deps; ADMIN, 3, 3; AddAccounts { accounts: vec![
+            ("investors".to_string(), alice.clone()),
+            ("investors".to_string(), bob.clone()),
+            ("advisors".to_string(),  carol.clone())] } == ok!());
+        let mut updated = s.clone();
+        updated.add_account("investors", alice.clone()).unwrap();
+        updated.add_account("investors", bob.clone()).unwrap();
+        updated.add_account("advisors",  carol.clone()).unwrap();
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: updated }); }
+
+}
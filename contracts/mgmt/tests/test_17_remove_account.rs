@@ -0,0 +1,80 @@
+#![cfg(test)]
+#![allow(unused_macros)]
+#![allow(non_snake_case)]
+
+#[macro_use] extern crate sienna_mgmt;
+#[macro_use] extern crate kukumba;
+#[macro_use] mod helpers; use helpers::{harness, mock_env, key};
+use cosmwasm_std::{HumanAddr, Uint128};
+use sienna_schedule::{Schedule, Pool, Account, ScheduleError};
+
+kukumba! {
+
+    #[ok_remove_and_update_before_launch]
+    given "an instance with a partial pool" {
+        harness!(deps; ADMIN, ALICE, BOB, TYPO, STRANGER);
+        let alice = Account::immediate("alice", &ALICE, 100);
+        let typo  = Account::immediate("bob",   &TYPO,  100);
+        let bob   = Account::immediate("bob",   &BOB,   50);
+        let s = Schedule::new(&[Pool::partial("pool", 300, &[alice.clone(), typo.clone()])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!()); }
+    when "someone other than the admin tries to remove or update an account"
+    then "they are denied" {
+        tx!(deps; STRANGER, 1, 1; RemoveAccount {
+            pool_name: "pool".to_string(), account_name: "alice".to_string() } == err!(auth));
+        tx!(deps; STRANGER, 1, 1; UpdateAccount {
+            pool_name: "pool".to_string(), account_name: "bob".to_string(), account: bob.clone()
+        } == err!(auth)); }
+    when "the admin fixes an account with a wrong address and amount"
+    then "it is replaced in place" {
+        tx!(deps; ADMIN, 2, 2; UpdateAccount {
+            pool_name: "pool".to_string(), account_name: "bob".to_string(), account: bob.clone()
+        } == ok!());
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: Schedule::new(&[
+            Pool::partial("pool", 300, &[alice.clone(), bob.clone()])]) }); }
+    when "the admin removes an account"
+    then "its funds go back to the pool" {
+        tx!(deps; ADMIN, 3, 3; RemoveAccount {
+            pool_name: "pool".to_string(), account_name: "alice".to_string() } == ok!());
+        q!(deps; Schedule { key: key(&ADMIN), address: None } == Schedule { schedule: Schedule::new(&[
+            Pool::partial("pool", 300, &[bob.clone()])]) });
+        tx!(deps; ADMIN, 4, 4; RemoveAccount {
+            pool_name: "pool".to_string(), account_name: "alice".to_string()
+        } == err!(schedule: ScheduleError::AccountNotFound {
+            pool: "pool".to_string(), account: "alice".to_string() })); }
+
+    #[no_remove_after_unlock_or_claim]
+    given "a launched instance where an address has an unlocked account and one that starts later" {
+        harness!(deps; ADMIN, ALICE, BOB);
+        let CLAIMED = MGMTError!(CLAIMED);
+        let NOTHING = MGMTError!(NOTHING);
+        let mut now   = Account::immediate("now", &ALICE, 100);
+        let mut later = Account::periodic("later", &ALICE, 100, 0, 1000, 10, 100);
+        now.shared_address   = true;
+        later.shared_address = true;
+        let bob = Account::periodic("bob", &BOB, 100, 0, 1000, 10, 100);
+        let s = Schedule::new(&[Pool::full("pool", &[now, later, bob])]);
+        tx!(deps; ADMIN, 0, 0; Configure { schedule: s.clone() } == ok!());
+        tx!(deps; ADMIN, 1, 1; Launch {} == ok!(launched: s.total)); }
+    when "the admin tries to remove an account that has unlocked funds"
+    then "that fails" {
+        tx!(deps; ADMIN, 2, 2; RemoveAccount {
+            pool_name: "pool".to_string(), account_name: "now".to_string()
+        } == err!(schedule: ScheduleError::AlreadyUnlocked { account: "now".to_string() })); }
+    when "the admin tries to remove an account whose address has claimed"
+    then "that fails" {
+        tx!(deps; ALICE, 3, 3; Claim { recipient: None, amount: None }
+            == ok!(claimed: ALICE, Uint128::from(100u128)));
+        tx!(deps; ADMIN, 4, 4; RemoveAccount {
+            pool_name: "pool".to_string(), account_name: "later".to_string() } == err!(CLAIMED)); }
+    when "the admin removes an account that hasn't started vesting and hasn't claimed"
+    then "the pool becomes partial" {
+        tx!(deps; ADMIN, 5, 5; RemoveAccount {
+            pool_name: "pool".to_string(), account_name: "bob".to_string() } == ok!());
+        tx!(deps; BOB, 6, 2000; Claim { recipient: None, amount: None }
+            == err!(NOTHING));
+        tx!(deps; ADMIN, 7, 7; AddAccount {
+            pool_name: "pool".to_string(), account: Account::immediate("bob", &BOB, 100)
+        } == ok!()); }
+
+}
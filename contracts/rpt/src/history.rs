@@ -0,0 +1,95 @@
+//! Log of past distributions, and how much each recipient has received in total
+//!
+//! Entries are stored one per key, so that appending doesn't require loading
+//! the whole log. The totals are kept in a single `LinearMap`, since there are
+//! only as many of them as there have been recipients.
+
+use cosmwasm_std::{
+    Storage, ReadonlyStorage, Api, StdResult, Uint128, HumanAddr, CanonicalAddr, to_vec, from_slice
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use linear_map::LinearMap;
+
+/// Storage prefix under which entries are stored, by index
+pub const PREFIX_DISTRIBUTIONS: &[u8] = b"distributions";
+
+/// Storage key under which the number of entries is stored
+pub const KEY_DISTRIBUTION_COUNT: &[u8] = b"distribution_count";
+
+/// Storage key under which the total received by each recipient is stored
+pub const KEY_TOTALS: &[u8] = b"totals";
+
+/// A single distribution.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct DistributionEntry<A> {
+    /// Block time
+    pub time:      u64,
+    /// Block height
+    pub height:    u64,
+    /// How much was received since the previous distribution
+    pub received:  Uint128,
+    /// How much was sent to each recipient
+    pub payouts:   LinearMap<A, Uint128>,
+    /// How much was kept for the next distribution
+    pub remainder: Uint128,
+}
+impl DistributionEntry<CanonicalAddr> {
+    pub fn humanize <A: Api> (&self, api: &A) -> StdResult<DistributionEntry<HumanAddr>> {
+        Ok(DistributionEntry {
+            time:      self.time,
+            height:    self.height,
+            received:  self.received,
+            payouts:   self.payouts.humanize(api)?,
+            remainder: self.remainder
+        })
+    }
+}
+
+/// Number of entries in the log
+pub fn distribution_count <S: ReadonlyStorage> (storage: &S) -> StdResult<u64> {
+    match storage.get(KEY_DISTRIBUTION_COUNT) {
+        Some(count) => from_slice(&count),
+        None => Ok(0)
+    }
+}
+
+/// Append an entry to the log and add its payouts to the totals, returning its index
+pub fn log_distribution <S: Storage> (
+    storage: &mut S, entry: &DistributionEntry<CanonicalAddr>
+) -> StdResult<u64> {
+    let index = distribution_count(storage)?;
+    PrefixedStorage::new(PREFIX_DISTRIBUTIONS, storage).set(&index.to_be_bytes(), &to_vec(entry)?);
+    storage.set(KEY_DISTRIBUTION_COUNT, &to_vec(&(index + 1))?);
+    let mut totals = get_totals(storage)?;
+    for (recipient, amount) in entry.payouts.0.iter() {
+        let total = totals.get(recipient).copied().unwrap_or_else(Uint128::zero);
+        totals.insert(recipient.clone(), Uint128::from(total.u128() + amount.u128()));
+    }
+    storage.set(KEY_TOTALS, &to_vec(&totals)?);
+    Ok(index)
+}
+
+/// Up to `limit` entries, starting from the `start`-th
+pub fn get_distributions <S: ReadonlyStorage> (
+    storage: &S, start: u64, limit: u64
+) -> StdResult<Vec<DistributionEntry<CanonicalAddr>>> {
+    let end = u64::min(start.saturating_add(limit), distribution_count(storage)?);
+    let distributions = ReadonlyPrefixedStorage::new(PREFIX_DISTRIBUTIONS, storage);
+    let mut entries = vec![];
+    for index in start..end {
+        if let Some(entry) = distributions.get(&index.to_be_bytes()) {
+            entries.push(from_slice(&entry)?)
+        }
+    }
+    Ok(entries)
+}
+
+/// How much each recipient has received so far, in order of first payout
+pub fn get_totals <S: ReadonlyStorage> (storage: &S) -> StdResult<LinearMap<CanonicalAddr, Uint128>> {
+    match storage.get(KEY_TOTALS) {
+        Some(totals) => from_slice(&totals),
+        None => Ok(LinearMap::new())
+    }
+}
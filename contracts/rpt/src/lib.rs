@@ -1,17 +1,52 @@
 #[macro_use] extern crate fadroma;
 
 // TODO(fadroma): we don't really need these to be public (see note in `mgmt`)
-pub use secret_toolkit::{snip20::handle::transfer_msg, utils::space_pad};
+pub use secret_toolkit::{
+    snip20::{handle::{transfer_msg, send_msg, set_viewing_key_msg}, query::balance_query},
+    utils::space_pad
+};
 pub use sienna_mgmt::msg::{Query as MGMTQuery, Response as MGMTResponse, Handle as MGMTHandle};
+pub use sienna_mgmt::auth::{create_viewing_key, set_viewing_key, check_viewing_key};
 pub use sienna_migration::{ContractStatus, ContractStatusLevel, is_operational, can_set_status};
 pub use linear_map::LinearMap;
 pub use cosmwasm_std::{QueryRequest, WasmQuery};
 
+pub mod history; pub use history::DistributionEntry;
+use history::{log_distribution, get_distributions, distribution_count, get_totals};
+
 /// Default value for Secret Network block size (used for padding)
 pub const BLOCK_SIZE: usize = 256;
 
+/// Default number of entries per page of `History`
+pub const HISTORY_PAGE_SIZE: u32 = 30;
+
+/// Maximum number of entries per page of `History`
+pub const HISTORY_MAX_PAGE_SIZE: u32 = 100;
+
 /// Into what parts to split the received amount
-pub type Config<T> = LinearMap<T, Uint128>;
+pub type Config<T> = LinearMap<T, Allocation>;
+
+/// A recipient's part of the portion. Recipients that are contracts can have a `callback`:
+/// the `msg` to pass to their `Receive` handler. These are paid with SNIP20 `Send`
+/// instead of `Transfer`.
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Debug, PartialEq)]
+pub struct Allocation {
+    pub amount:   Uint128,
+    #[serde(default)]
+    pub callback: Option<Binary>
+}
+
+/// How to split the received amount between the recipients in `Config`
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionMode {
+    /// Each recipient gets their amount from `Config` once for every whole `portion`
+    /// received; whatever is left over is carried over to the next `Vest`.
+    #[default]
+    Portions,
+    /// Everything received is split in proportion to the amounts in `Config`.
+    ProRata
+}
 
 /// Code hashes for MGMT and SNIP20
 pub type CodeHash = String;
@@ -23,7 +58,6 @@ pub type ContractLink<T> = (T, CodeHash);
 #[macro_export] macro_rules! RPTError {
     (CORRUPTED) => { "Contract has entered a state that violates core assumptions." };
     (TOTAL: $x:expr, $y:expr) => { format!("Allocations must add up to {}, not {}", &$x, &$y) };
-    (MGMT) => { "Main vesting contract returned unexpected response." };
 }
 
 contract!(
@@ -32,31 +66,45 @@ contract!(
         admin:   CanonicalAddr,
         /// The portion size of the RPT pool. Immutable as per requirements.
         portion: Uint128,
-        /// How the portion is split. Must add up to `portion`.
+        /// How the portion is split, and which recipients to notify when paying them.
+        /// Must add up to `portion`.
         config:  Config<CanonicalAddr>,
+        /// Whether to pay out whole portions or split everything pro rata.
+        mode:    DistributionMode,
         /// A link to the token.
         token:   ContractLink<CanonicalAddr>,
         /// A link to the management contract which gives tokens.
         mgmt:    ContractLink<CanonicalAddr>,
+        /// Used to query this contract's balance from the token.
+        viewing_key: String,
+        /// What was left over after splitting the claimed funds into portions.
+        /// Stays in the balance and is distributed with the next `Vest`.
+        remainder: Uint128,
         /// The paused/migration flag.
         status:  ContractStatus
     }
 
     /// Requires MGMT and SNIP20 to be deployed. Their addresses and hashes,
     /// as well as the pool and account names, can't be changed after init.
+    /// `entropy` is used to generate the viewing key for SNIP20
+    /// (see the `SetViewingKey` handle).
     [Init] (deps, env, msg: {
         portion: Uint128,
         config:  Config<HumanAddr>,
         token:   ContractLink<HumanAddr>,
-        mgmt:    ContractLink<HumanAddr>
+        mgmt:    ContractLink<HumanAddr>,
+        entropy: String
     }) {
         validate(portion, &config)?;
         State {
             portion,
             admin:  deps.api.canonical_address(&env.message.sender)?,
-            config: config.canonize(&deps.api)?,
+            config: merge_config(&deps.api, &config, &Config::new())?,
+            mode:   DistributionMode::default(),
             token:  (deps.api.canonical_address(&token.0)?, token.1),
             mgmt:   (deps.api.canonical_address(&mgmt.0)?,  mgmt.1),
+            viewing_key: create_viewing_key(&env, &entropy),
+            remainder: Uint128::zero(),
             status: ContractStatus::default()
         }
     }
@@ -67,20 +115,54 @@ contract!(
                 portion: state.portion,
                 status:  state.status,
                 config:  state.config.humanize(&deps.api)?,
+                mode:    state.mode,
                 token:   (deps.api.human_address(&state.token.0)?, state.token.1.clone()),
                 mgmt:    (deps.api.human_address(&state.mgmt.0)?,  state.mgmt.1.clone())
             })
         }
+
+        /// Return how much has been claimed but not yet distributed
+        Remainder () {
+            Ok(Response::Remainder { remainder: state.remainder })
+        }
+
+        /// Return a page of past distributions, oldest first,
+        /// and the total number of entries (requires the admin's viewing key)
+        History (key: String, start: Option<u64>, limit: Option<u32>) {
+            is_admin_key(&deps.storage, &state, &key)?;
+            let limit = u32::min(limit.unwrap_or(HISTORY_PAGE_SIZE), HISTORY_MAX_PAGE_SIZE);
+            let distributions = get_distributions(&deps.storage, start.unwrap_or(0), limit as u64)?
+                .iter().map(|entry| entry.humanize(&deps.api)).collect::<StdResult<Vec<_>>>()?;
+            Ok(Response::History { distributions, total: distribution_count(&deps.storage)? })
+        }
+
+        /// Return how much each recipient has received so far
+        /// (requires the admin's viewing key)
+        Totals (key: String) {
+            is_admin_key(&deps.storage, &state, &key)?;
+            Ok(Response::Totals { totals: get_totals(&deps.storage)?.humanize(&deps.api)? })
+        }
     }
 
     [Response] {
         Status {
             portion: Uint128,
             config:  Config<HumanAddr>,
+            mode:    DistributionMode,
             token:   ContractLink<HumanAddr>,
             mgmt:    ContractLink<HumanAddr>,
             status:  ContractStatus
         }
+        Remainder {
+            remainder: Uint128
+        }
+        History {
+            distributions: Vec<DistributionEntry<HumanAddr>>,
+            total:         u64
+        }
+        Totals {
+            totals: LinearMap<HumanAddr, Uint128>
+        }
     }
 
     [Handle] (deps, env, state, msg) -> Response {
@@ -109,49 +191,130 @@ contract!(
             Ok(HandleResponse::default())
         }
 
-        /// Set how funds will be split.
+        /// Set the viewing key with which the admin can query the distribution history.
+        SetAdminKey (key: String) {
+            is_admin(&deps, &env, &state)?;
+
+            set_viewing_key(&mut deps.storage, &state.admin, &key);
+
+            Ok(HandleResponse::default())
+        }
+
+        /// Set how funds will be split, and which recipients to pay with `Send`.
+        /// Recipients that are given no `callback` keep the one they had;
+        /// an empty `callback` removes it.
         Configure (config: Config<HumanAddr>) {
             is_admin(&deps, &env, &state)?;
             is_operational(&state.status)?;
             validate(state.portion, &config)?;
 
-            state.config = config.canonize(&deps.api)?;
+            state.config = merge_config(&deps.api, &config, &state.config)?;
 
             save_state!();
             Ok(HandleResponse::default())
         }
 
-        /// Receive and distribute funds.
-        /// `WARNING` a cliff on the RPT account could confuse this?
+        /// Set whether to pay out whole portions or split everything pro rata.
+        SetMode (mode: DistributionMode) {
+            is_admin(&deps, &env, &state)?;
+            is_operational(&state.status)?;
+
+            state.mode = mode;
+
+            save_state!();
+            Ok(HandleResponse::default())
+        }
+
+        /// Register this contract's viewing key with SNIP20, so that it can query
+        /// its balance. Needs to be called once after init, before the first `Vest`.
+        SetViewingKey () {
+            is_admin(&deps, &env, &state)?;
+
+            let (token_addr, token_hash) = &state.token;
+            let messages = vec![
+                set_viewing_key_msg(
+                    state.viewing_key.clone(), None, BLOCK_SIZE,
+                    token_hash.clone(), deps.api.human_address(&token_addr)?
+                )?
+            ];
+
+            Ok(HandleResponse { messages, data: None, log: vec![] })
+        }
+
+        /// Claim funds from MGMT and distribute them. The distribution is a separate
+        /// `Distribute` message that runs after the claim, so that what gets distributed
+        /// is what was actually received, and not what MGMT reported as claimable.
+        /// Fails if there is nothing to claim; use `Distribute` to pay out what
+        /// the contract already holds.
         Vest () {
             is_operational(&state.status)?;
 
-            let claimable = query_claimable(&deps, &env, &state.mgmt)?;
+            let mut claim = to_binary(&MGMTHandle::Claim { recipient: None, amount: None })?;
+            space_pad(&mut claim.0, BLOCK_SIZE);
+            let mut distribute = to_binary(&crate::msg::Handle::Distribute {})?;
+            space_pad(&mut distribute.0, BLOCK_SIZE);
+            let messages = vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr:      deps.api.human_address(&state.mgmt.0)?,
+                    callback_code_hash: state.mgmt.1.clone(),
+                    send:               vec![],
+                    msg:                claim,
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr:      env.contract.address.clone(),
+                    callback_code_hash: env.contract_code_hash.clone(),
+                    send:               vec![],
+                    msg:                distribute,
+                })
+            ];
+
+            Ok(HandleResponse { messages, data: None, log: vec![] })
+        }
+
+        /// Distribute this contract's balance. Called by the contract itself as the
+        /// second half of `Vest`, and can be called by anyone to distribute what was
+        /// carried over when there is nothing new to claim. In `Portions` mode, whatever
+        /// doesn't add up to a whole portion stays in the balance, and is distributed
+        /// on the next call.
+        Distribute () {
+            is_operational(&state.status)?;
+
             let mut messages = vec![];
-            let mut msg = to_binary(&MGMTHandle::Claim {})?;
-            space_pad(&mut msg.0, BLOCK_SIZE);
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr:      deps.api.human_address(&state.mgmt.0)?,
-                callback_code_hash: state.mgmt.1.clone(),
-                send:               vec![],
-                msg,
-            }));
-            let claimable = claimable.u128();
-            let portion   = state.portion.u128();
-            let portions  = claimable / portion;
-            let remainder = claimable % portion;
-            for (addr, amount) in state.config.0.iter() {
-                let msg = transfer(&deps.api, &state, addr, Uint128::from(amount.u128()*portions));
+            let available = query_balance(&deps, &env, &state)?.u128();
+            let (amounts, remainder): (Vec<(CanonicalAddr, u128)>, u128) = match state.mode {
+                DistributionMode::Portions => {
+                    let portion  = state.portion.u128();
+                    let portions = available / portion;
+                    let amounts  = state.config.0.iter()
+                        .map(|(addr, allocation)| (addr.clone(), allocation.amount.u128()*portions))
+                        .collect();
+                    (amounts, available % portion)
+                },
+                DistributionMode::ProRata =>
+                    (split_pro_rata(available, &state.config), 0)
+            };
+            // an empty `Send` could make a contract recipient fail the whole distribution
+            for (addr, amount) in amounts.iter().filter(|(_, amount)| *amount > 0) {
+                let msg = transfer(&deps.api, &state, addr, Uint128::from(*amount));
                 messages.push(msg?);
             }
+            log_distribution(&mut deps.storage, &DistributionEntry {
+                time:      env.block.time,
+                height:    env.block.height,
+                received:  Uint128::from(available.saturating_sub(state.remainder.u128())),
+                payouts:   LinearMap(amounts.into_iter()
+                    .map(|(addr, amount)| (addr, Uint128::from(amount))).collect()),
+                remainder: Uint128::from(remainder)
+            })?;
 
             let mut log = vec![];
             if remainder > 0 {
                 log.push(LogAttribute {
-                    key: "remainder (locked forever)".to_string(),
+                    key: "remainder".to_string(),
                     value: remainder.to_string()
                 });
             }
+            state.remainder = Uint128::from(remainder);
 
             save_state!();
             Ok(HandleResponse { messages, data: None, log })
@@ -159,24 +322,15 @@ contract!(
     }
 );
 
-fn query_claimable <S:Storage,A:Api,Q:Querier> (
-    deps: &Extern<S,A,Q>, env: &Env, mgmt: &ContractLink<CanonicalAddr>
+fn query_balance <S:Storage,A:Api,Q:Querier> (
+    deps: &Extern<S,A,Q>, env: &Env, state: &State
 ) -> StdResult<Uint128> {
-    let msg = MGMTQuery::Progress { address: env.contract.address.clone(), time: env.block.time };
-    let mut msg = to_binary(&msg)?;
-    space_pad(&mut msg.0, BLOCK_SIZE);
-    let response = deps.querier.query::<MGMTResponse>(
-        &QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr:      deps.api.human_address(&mgmt.0)?,
-            callback_code_hash: mgmt.1.clone(),
-            msg,
-        })
-    );
-    if let MGMTResponse::Progress { unlocked, claimed, .. } = response? {
-        Ok((unlocked - claimed)?)
-    } else {
-        Err(StdError::GenericErr { msg: RPTError!(MGMT).to_string(), backtrace: None })
-    }
+    let (token_addr, token_hash) = &state.token;
+    let balance = balance_query(
+        &deps.querier, env.contract.address.clone(), state.viewing_key.clone(), BLOCK_SIZE,
+        token_hash.clone(), deps.api.human_address(&token_addr)?
+    )?;
+    Ok(balance.amount)
 }
 
 fn validate <T> (portion: Uint128, config: &Config<T>) -> StdResult<()> {
@@ -188,12 +342,79 @@ fn validate <T> (portion: Uint128, config: &Config<T>) -> StdResult<()> {
     }
 }
 
-fn sum_config <T> (map: &LinearMap<T, Uint128>) -> Uint128 {
+/// Canonize a new config, keeping the `callback` of each recipient that isn't given one,
+/// and removing the ones that are set to an empty `msg`.
+fn merge_config <A:Api> (
+    api: &A, config: &Config<HumanAddr>, previous: &Config<CanonicalAddr>
+) -> StdResult<Config<CanonicalAddr>> {
+    let mut merged = Config::new();
+    for (addr, Allocation { amount, callback }) in config.0.iter() {
+        let addr = api.canonical_address(addr)?;
+        let callback = match callback {
+            None => previous.get(&addr).and_then(|allocation| allocation.callback.clone()),
+            Some(msg) if msg.0.is_empty() => None,
+            Some(msg) => Some(msg.clone())
+        };
+        merged.insert(addr, Allocation { amount: *amount, callback });
+    }
+    Ok(merged)
+}
+
+fn sum_config <T> (config: &Config<T>) -> Uint128 {
     let mut total = Uint128::zero();
-    for (_, amount) in map.0.iter() { total += *amount; }
+    for (_, allocation) in config.0.iter() { total += allocation.amount; }
     total
 }
 
+/// Split `amount` in proportion to the amounts in `config`, rounding down.
+/// What is lost to rounding (less than 1 per recipient) is given out 1 at a time
+/// to the recipients whose shares were rounded down the most
+/// (to those that come first in `config` in case of a tie).
+fn split_pro_rata <T: Clone> (amount: u128, config: &Config<T>) -> Vec<(T, u128)> {
+    let total = sum_config(config).u128();
+    if total == 0 {
+        return config.0.iter().map(|(addr, _)| (addr.clone(), 0)).collect()
+    }
+    let shares: Vec<(u128, u128)> = config.0.iter()
+        .map(|(_, allocation)| mul_div(amount, allocation.amount.u128(), total)).collect();
+    let mut leftover = amount - shares.iter().map(|(share, _)| share).sum::<u128>();
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|a, b| shares[*b].1.cmp(&shares[*a].1)); // stable, so ties keep config order
+    let mut amounts: Vec<(T, u128)> = config.0.iter()
+        .zip(shares.iter()).map(|((addr, _), (share, _))| (addr.clone(), *share)).collect();
+    for index in order {
+        if leftover == 0 { break }
+        amounts[index].1 += 1;
+        leftover -= 1;
+    }
+    amounts
+}
+
+/// `x * y / z` and its remainder, without overflowing in the multiplication.
+/// The quotient must fit in a `u128` (which it does if `y <= z`).
+fn mul_div (x: u128, y: u128, z: u128) -> (u128, u128) {
+    const LOW: u128 = u64::MAX as u128;
+    // 256-bit product as (high, low) halves
+    let (x1, x0, y1, y0) = (x >> 64, x & LOW, y >> 64, y & LOW);
+    let (p00, p01, p10, p11) = (x0 * y0, x0 * y1, x1 * y0, x1 * y1);
+    let mid  = (p00 >> 64) + (p01 & LOW) + (p10 & LOW);
+    let low  = (p00 & LOW) | (mid << 64);
+    let high = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    // long division, one bit at a time
+    let (mut quotient, mut remainder) = (0u128, 0u128);
+    for i in (0..256).rev() {
+        let bit = if i >= 128 { (high >> (i - 128)) & 1 } else { (low >> i) & 1 };
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | bit;
+        quotient <<= 1;
+        if carry == 1 || remainder >= z {
+            remainder = remainder.wrapping_sub(z);
+            quotient |= 1;
+        }
+    }
+    (quotient, remainder)
+}
+
 fn is_admin <S:Storage,A:Api,Q:Querier> (
     deps: &Extern<S,A,Q>, env: &Env, state: &State
 ) -> StdResult<()> {
@@ -204,11 +425,61 @@ fn is_admin <S:Storage,A:Api,Q:Querier> (
     }
 }
 
+fn is_admin_key <S:Storage> (storage: &S, state: &State, key: &str) -> StdResult<()> {
+    if check_viewing_key(storage, &state.admin, key) {
+        Ok(())
+    } else {
+        Err(StdError::Unauthorized { backtrace: None })
+    }
+}
+
+/// Pay a recipient, notifying it if it has a callback
 fn transfer <A:Api> (
     api: &A, state: &State, recipient: &CanonicalAddr, amount: Uint128
 ) -> StdResult<CosmosMsg> {
     let (token_addr, token_hash) = &state.token;
     let token_addr = api.human_address(&token_addr)?;
+    let callback   = state.config.get(recipient).and_then(|allocation| allocation.callback.clone());
     let recipient  = api.human_address(&recipient)?;
-    transfer_msg(recipient, amount, None, BLOCK_SIZE, token_hash.clone(), token_addr)
+    match callback {
+        Some(msg) => send_msg(recipient, amount, Some(msg), None, BLOCK_SIZE, token_hash.clone(), token_addr),
+        None => transfer_msg(recipient, amount, None, BLOCK_SIZE, token_hash.clone(), token_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{HumanAddr, Uint128};
+    use linear_map::LinearMap;
+    use crate::{Config, Allocation, split_pro_rata, mul_div};
+    fn config (weights: &[u128]) -> Config<HumanAddr> {
+        LinearMap(weights.iter().enumerate()
+            .map(|(i, weight)| (HumanAddr::from(format!("R{}", i)), Allocation {
+                amount: Uint128::from(*weight), callback: None
+            }))
+            .collect())
+    }
+    fn amounts (split: Vec<(HumanAddr, u128)>) -> Vec<u128> {
+        split.into_iter().map(|(_, amount)| amount).collect()
+    }
+    #[test] fn test_mul_div () {
+        assert_eq!(mul_div(7, 3, 5), (4, 1));
+        assert_eq!(mul_div(0, u128::MAX, 1), (0, 0));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), (u128::MAX, 0));
+        assert_eq!(mul_div(u128::MAX, 2, 3), (226854911280625642308916404954512140970, 0));
+        assert_eq!(mul_div(u128::MAX, 1, 2), (u128::MAX / 2, 1));
+    }
+    #[test] fn test_split_pro_rata () {
+        // exact split
+        assert_eq!(amounts(split_pro_rata(2500, &config(&[1000, 1500]))), vec![1000, 1500]);
+        // equal fractions: the leftover goes to those that come first
+        assert_eq!(amounts(split_pro_rata(2, &config(&[1, 1, 1]))), vec![1, 1, 0]);
+        // the leftover goes to the largest fractions: 1.7, 3.4, 5.1, 6.8
+        assert_eq!(amounts(split_pro_rata(17, &config(&[1, 2, 3, 4]))), vec![2, 3, 5, 7]);
+        // nothing to split
+        assert_eq!(amounts(split_pro_rata(0, &config(&[1, 2]))), vec![0, 0]);
+        // amount * weight overflows
+        assert_eq!(amounts(split_pro_rata(u128::MAX, &config(&[u128::MAX / 2, u128::MAX / 2 + 1]))),
+            vec![u128::MAX / 2, u128::MAX / 2 + 1]);
+    }
 }
@@ -19,10 +19,10 @@ use cosmwasm_std::{
 use sienna_rpt::{
     init, query, handle,
     msg::{Init as RPTInit, Query as RPTQuery, Handle as RPTHandle, Response as RPTResponse},
-    ContractStatus, ContractStatusLevel
+    ContractStatus, ContractStatusLevel, DistributionMode, DistributionEntry, Config, Allocation
 };
 use sienna_mgmt::msg::{Query as MGMTQuery, Response as MGMTResponse, Handle as MGMTHandle};
-use snip20_reference_impl::msg::{HandleMsg as TokenHandle};
+use snip20_reference_impl::msg::{HandleMsg as TokenHandle, QueryAnswer as TokenQueryAnswer};
 use linear_map::LinearMap;
 
 kukumba!(
@@ -36,15 +36,16 @@ kukumba!(
         let mut deps = Extern {
             storage: MockStorage::default(),
             api:     MockApi::new(45),
-            querier: MockQuerier { portion: 2500 } }
-        let initial_config = LinearMap(vec![
-            (ADMIN.clone(), Uint128::from(2500u128))]);
-        let updated_config = LinearMap(vec![
+            querier: MockQuerier { balance: 0 } }
+        let initial_config = config(&LinearMap(vec![
+            (ADMIN.clone(), Uint128::from(2500u128))]));
+        let updated_payouts = LinearMap(vec![
             (TOKEN1.clone(), Uint128::from(1000u128)),
             (TOKEN2.clone(), Uint128::from(1500u128)) ]);
-        let invalid_config = LinearMap(vec![
+        let updated_config = config(&updated_payouts);
+        let invalid_config = config(&LinearMap(vec![
             (TOKEN1.clone(), Uint128::from(1001u128)),
-            (TOKEN2.clone(), Uint128::from(1500u128)) ]); }
+            (TOKEN2.clone(), Uint128::from(1500u128)) ])); }
     when "someone deploys the contract" {
         assert_eq!(
             0,
@@ -53,6 +54,7 @@ kukumba!(
                 config:  initial_config.clone(),
                 token:   (HumanAddr::from("token"), String::new()),
                 mgmt:    (HumanAddr::from("mgmt"),  String::new()),
+                entropy: "entropy".to_string(),
             }).unwrap().messages.len(),
             "deploy failed" ); }
     then "they become admin"
@@ -62,6 +64,7 @@ kukumba!(
         let status_initial = RPTResponse::Status {
             portion: Uint128::from(2500u128),
             config:  initial_config,
+            mode:    DistributionMode::Portions,
             token:   (HumanAddr::from("token"), String::new()),
             mgmt:    (HumanAddr::from("mgmt"),  String::new()),
             status:  ContractStatus {
@@ -93,6 +96,7 @@ kukumba!(
         let expected_valid = RPTResponse::Status {
             portion: Uint128::from(2500u128),
             config:  updated_config.clone(),
+            mode:    DistributionMode::Portions,
             token:   (HumanAddr::from("token"), String::new()),
             mgmt:    (HumanAddr::from("mgmt"),  String::new()),
             status:  ContractStatus {
@@ -107,37 +111,198 @@ kukumba!(
             }).unwrap();
             status(&deps) };
         assert_eq!(expected_valid, actual_valid, "admin was unable to set valid config"); }
+    when "the admin registers the contract's viewing key"
+    then "a message setting the key is sent to the token"
+    and "noone else can do that" {
+        assert_eq!(
+            handle(&mut deps, mock_env(2, 2, &STRANGER), RPTHandle::SetViewingKey {}),
+            Err(cosmwasm_std::StdError::Unauthorized { backtrace: None }));
+        let messages = handle(
+            &mut deps, mock_env(2, 2, &ADMIN), RPTHandle::SetViewingKey {}
+        ).unwrap().messages;
+        assert_eq!(messages.len(), 1, "unexpected message count");
+        if let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) = messages.get(0).unwrap() {
+            assert_eq!(*contract_addr, HumanAddr::from("token"));
+            if let TokenHandle::SetViewingKey { key, .. } = from_binary::<TokenHandle>(&msg).unwrap() {
+                assert!(key.starts_with("api_key_"), "unexpected viewing key");
+            } else {
+                panic!("unexpected message");
+            }
+        } else {
+            panic!("unexpected message");
+        } }
     when "anyone calls the vest method"
     then "the contract claims funds from mgmt"
-    and "it distributes them to the configured recipients" {
+    and "then tells itself to distribute them" {
         let messages = handle(
             &mut deps, mock_env(2, 2, &STRANGER), RPTHandle::Vest {}
         ).unwrap().messages;
-        assert_eq!(messages.len(), 3, "unexpected message count");
-
-        // check claim from token
-        if let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = messages.get(0).unwrap() {
+        assert_eq!(messages.len(), 2, "unexpected message count");
+        if let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) = messages.get(0).unwrap() {
+            assert_eq!(*contract_addr, HumanAddr::from("mgmt"));
             if let MGMTHandle::Claim {..} = from_binary::<MGMTHandle>(&msg).unwrap() {} else {
                 panic!("unexpected 1st message");
             }
         } else {
             panic!("unexpected 1st message");
         }
-        // check vestings to recipients
-        for i in 1..3 {
-            if let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = messages.get(i).unwrap() {
-                if let TokenHandle::Transfer {recipient,amount,..} = from_binary::<TokenHandle>(&msg).unwrap() {
-                    let (expected_recipient, expected_amount) = updated_config.0.get(i-1).unwrap();
-                    assert_eq!(*expected_recipient, recipient);
-                    assert_eq!(*expected_amount,    amount);
-                } else {
-                    panic!("unexpected message #{}", i+1);
-                }
+        if let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) = messages.get(1).unwrap() {
+            assert_eq!(*contract_addr, HumanAddr::from("rpt"));
+            if let RPTHandle::Distribute {} = from_binary::<RPTHandle>(&msg).unwrap() {} else {
+                panic!("unexpected 2nd message");
+            }
+        } else {
+            panic!("unexpected 2nd message");
+        } }
+    when "the contract distributes what it received"
+    then "the configured recipients get their parts" {
+        let response = distribute(&mut deps, 2500);
+        assert_eq!(transfers(&response), updated_payouts.0);
+        assert_eq!(remainder(&deps), Uint128::zero()); }
+    when "it received an amount that is not a multiple of the portion"
+    then "what is left over is kept"
+    and "it is distributed with the next claim" {
+        let response = distribute(&mut deps, 3700);
+        assert_eq!(transfers(&response), updated_payouts.0);
+        assert_eq!(remainder(&deps), Uint128::from(1200u128));
+        let response = distribute(&mut deps, 1200 + 1300);
+        assert_eq!(transfers(&response), updated_payouts.0);
+        assert_eq!(remainder(&deps), Uint128::zero()); }
+    when "it received less than a portion"
+    then "nothing is distributed until it has enough" {
+        let response = distribute(&mut deps, 2499);
+        assert_eq!(response.messages.len(), 0, "unexpected message count");
+        assert_eq!(remainder(&deps), Uint128::from(2499u128)); }
+    when "the admin switches to pro-rata mode"
+    then "any amount is split in proportion to the configured amounts"
+    and "what is lost to rounding goes to whoever was rounded down the most" {
+        assert_eq!(
+            handle(&mut deps, mock_env(5, 5, &STRANGER), RPTHandle::SetMode {
+                mode: DistributionMode::ProRata
+            }),
+            Err(cosmwasm_std::StdError::Unauthorized { backtrace: None }));
+        handle(&mut deps, mock_env(5, 5, &ADMIN), RPTHandle::SetMode {
+            mode: DistributionMode::ProRata
+        }).unwrap();
+        let response = distribute(&mut deps, 2499 + 1002);
+        assert_eq!(transfers(&response), vec![
+            (TOKEN1.clone(), Uint128::from(1400u128)),
+            (TOKEN2.clone(), Uint128::from(2101u128))]);
+        assert_eq!(remainder(&deps), Uint128::zero()); }
+    when "the amount is so large that multiplying it by an allocation would overflow"
+    then "the split is still correct" {
+        let amount = 1_000_000_000_000_000_000_000_000_000_000_000_001u128;
+        assert!(amount.checked_mul(1500).is_none());
+        let response = distribute(&mut deps, amount);
+        assert_eq!(transfers(&response), vec![
+            (TOKEN1.clone(), Uint128::from(400_000_000_000_000_000_000_000_000_000_000_000u128)),
+            (TOKEN2.clone(), Uint128::from(600_000_000_000_000_000_000_000_000_000_000_001u128))]); }
+    when "someone other than the admin asks for the history of distributions"
+    then "they are denied" {
+        assert_eq!(
+            handle(&mut deps, mock_env(8, 8, &STRANGER), RPTHandle::SetAdminKey { key: "key".into() }),
+            Err(cosmwasm_std::StdError::Unauthorized { backtrace: None }));
+        handle(&mut deps, mock_env(8, 8, &ADMIN), RPTHandle::SetAdminKey { key: "key".into() }).unwrap();
+        assert_eq!(
+            query(&deps, RPTQuery::History { key: "wrong".into(), start: None, limit: None }),
+            Err(cosmwasm_std::StdError::Unauthorized { backtrace: None }));
+        assert_eq!(
+            query(&deps, RPTQuery::Totals { key: "wrong".into() }),
+            Err(cosmwasm_std::StdError::Unauthorized { backtrace: None })); }
+    when "the admin asks for the history of distributions"
+    then "they see what was received and paid out each time"
+    and "how much each recipient has received in total" {
+        let (distributions, total) = history(&deps, None, None);
+        assert_eq!(total, 6);
+        assert_eq!(distributions.len(), 6);
+        assert_eq!(distributions[1], DistributionEntry {
+            time:      3,
+            height:    3,
+            received:  Uint128::from(3700u128),
+            payouts:   updated_payouts.clone(),
+            remainder: Uint128::from(1200u128)
+        });
+        assert_eq!(distributions[2].received, Uint128::from(1300u128));
+        assert_eq!(history(&deps, Some(4), Some(1)), (vec![DistributionEntry {
+            time:      3,
+            height:    3,
+            received:  Uint128::from(1002u128),
+            payouts:   LinearMap(vec![
+                (TOKEN1.clone(), Uint128::from(1400u128)),
+                (TOKEN2.clone(), Uint128::from(2101u128))]),
+            remainder: Uint128::zero()
+        }], 6));
+        assert_eq!(totals(&deps), LinearMap(vec![
+            (TOKEN1.clone(), Uint128::from(400_000_000_000_000_000_000_000_000_000_004_400u128)),
+            (TOKEN2.clone(), Uint128::from(600_000_000_000_000_000_000_000_000_000_006_602u128))])); }
+    when "the admin sets a callback for a recipient that is a contract"
+    then "that recipient is paid with a send that notifies it"
+    and "the callback is kept when the config is changed without mentioning it" {
+        let callback = Binary::from(b"{\"deposit\":{}}".to_vec());
+        let mut with_callback = updated_config.clone();
+        with_callback.insert(TOKEN2.clone(), Allocation {
+            amount: Uint128::from(1500u128), callback: Some(callback.clone())
+        });
+        handle(&mut deps, mock_env(8, 8, &ADMIN), RPTHandle::Configure {
+            config: with_callback.clone()
+        }).unwrap();
+        handle(&mut deps, mock_env(8, 8, &ADMIN), RPTHandle::Configure {
+            config: updated_config.clone()
+        }).unwrap();
+        if let RPTResponse::Status { config, .. } = status(&deps) {
+            assert_eq!(config, with_callback);
+        } else {
+            panic!("unexpected response");
+        }
+        let response = distribute(&mut deps, 2500);
+        assert_eq!(transfers(&response), vec![(TOKEN1.clone(), Uint128::from(1000u128))]);
+        if let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = response.messages.get(1).unwrap() {
+            if let TokenHandle::Send { recipient, amount, msg, .. } = from_binary::<TokenHandle>(&msg).unwrap() {
+                assert_eq!(recipient, TOKEN2);
+                assert_eq!(amount,    Uint128::from(1500u128));
+                assert_eq!(msg,       Some(callback.clone()));
             } else {
-                panic!("unexpected message #{}", i+1);
+                panic!("unexpected message #2");
             }
+        } else {
+            panic!("unexpected message #2");
+        } }
+    when "the recipient with the callback has nothing to receive"
+    then "it isn't sent anything, so that it isn't notified of an empty payment" {
+        handle(&mut deps, mock_env(9, 9, &ADMIN), RPTHandle::SetMode {
+            mode: DistributionMode::Portions
+        }).unwrap();
+        let response = distribute(&mut deps, 2499);
+        assert_eq!(response.messages.len(), 0, "unexpected message count");
+        assert_eq!(remainder(&deps), Uint128::from(2499u128)); }
+    when "there is nothing left to claim from mgmt, so vesting fails at the claim"
+    then "anyone can still distribute what was carried over" {
+        handle(&mut deps, mock_env(10, 10, &ADMIN), RPTHandle::SetMode {
+            mode: DistributionMode::ProRata
+        }).unwrap();
+        deps.querier.balance = 2499;
+        let response = handle(
+            &mut deps, mock_env(10, 10, &STRANGER), RPTHandle::Distribute {}
+        ).unwrap();
+        assert_eq!(response.messages.len(), 2, "unexpected message count");
+        assert_eq!(transfers(&response), vec![(TOKEN1.clone(), Uint128::from(1000u128))]);
+        assert_eq!(remainder(&deps), Uint128::zero()); }
+    when "the admin sets an empty callback for the recipient that has one"
+    then "it is paid with a transfer again" {
+        let mut without_callback = updated_config.clone();
+        without_callback.insert(TOKEN2.clone(), Allocation {
+            amount: Uint128::from(1500u128), callback: Some(Binary::from(vec![]))
+        });
+        handle(&mut deps, mock_env(11, 11, &ADMIN), RPTHandle::Configure {
+            config: without_callback
+        }).unwrap();
+        if let RPTResponse::Status { config, .. } = status(&deps) {
+            assert_eq!(config, updated_config);
+        } else {
+            panic!("unexpected response");
         }
-    }
+        let response = distribute(&mut deps, 2500);
+        assert_eq!(transfers(&response), updated_payouts.0); }
 
 );
 
@@ -151,42 +316,74 @@ fn mock_env (height: u64, time: u64, sender: &HumanAddr) -> Env {
     }
 }
 
+/// A config without callbacks
+fn config (amounts: &LinearMap<HumanAddr, Uint128>) -> Config<HumanAddr> {
+    LinearMap(amounts.0.iter().map(|(addr, amount)| (addr.clone(), Allocation {
+        amount: *amount, callback: None
+    })).collect())
+}
+
 fn status<S:Storage,A:Api,Q:Querier> (deps: &Extern<S,A,Q>) -> RPTResponse {
     from_binary::<RPTResponse>(
         &query(&deps, RPTQuery::Status {}).unwrap()
     ).unwrap()
 }
 
+fn remainder<S:Storage,A:Api,Q:Querier> (deps: &Extern<S,A,Q>) -> Uint128 {
+    match from_binary::<RPTResponse>(&query(&deps, RPTQuery::Remainder {}).unwrap()).unwrap() {
+        RPTResponse::Remainder { remainder } => remainder,
+        _ => panic!("unexpected response")
+    }
+}
+
+/// Set the contract's token balance and have it distribute it
+fn distribute (deps: &mut Extern<MockStorage, MockApi, MockQuerier>, balance: u128) -> HandleResponse {
+    deps.querier.balance = balance;
+    handle(deps, mock_env(3, 3, &HumanAddr::from("rpt")), RPTHandle::Distribute {}).unwrap()
+}
+
+/// Recipients and amounts of the token transfers in a response
+fn transfers (response: &HandleResponse) -> Vec<(HumanAddr, Uint128)> {
+    response.messages.iter().filter_map(|msg| match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary::<TokenHandle>(&msg) {
+            Ok(TokenHandle::Transfer { recipient, amount, .. }) => Some((recipient, amount)),
+            _ => None
+        },
+        _ => None
+    }).collect()
+}
+
+fn history<S:Storage,A:Api,Q:Querier> (
+    deps: &Extern<S,A,Q>, start: Option<u64>, limit: Option<u32>
+) -> (Vec<DistributionEntry<HumanAddr>>, u64) {
+    match from_binary::<RPTResponse>(&query(&deps, RPTQuery::History { key: "key".into(), start, limit }).unwrap()).unwrap() {
+        RPTResponse::History { distributions, total } => (distributions, total),
+        _ => panic!("unexpected response")
+    }
+}
+
+fn totals<S:Storage,A:Api,Q:Querier> (deps: &Extern<S,A,Q>) -> LinearMap<HumanAddr, Uint128> {
+    match from_binary::<RPTResponse>(&query(&deps, RPTQuery::Totals { key: "key".into() }).unwrap()).unwrap() {
+        RPTResponse::Totals { totals } => totals,
+        _ => panic!("unexpected response")
+    }
+}
+
 //fn print_type_of<T>(_: &T) {
     //println!("{}", std::any::type_name::<T>())
 //}
+/// Answers balance queries to the token
 struct MockQuerier {
-    portion: u128
+    balance: u128
 }
 impl Querier for MockQuerier {
     fn raw_query (&self, bin_request: &[u8]) -> QuerierResult {
         let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
         match &request {
-            QueryRequest::Wasm(msg) => {
-                match msg {
-                    WasmQuery::Smart { contract_addr, .. } => {
-                        let mgmt = HumanAddr::from("mgmt");
-                        match &contract_addr {
-                            mgmt => {
-                                let response = MGMTResponse::Progress {
-                                    time:     0u64,
-                                    launched: 0u64,
-                                    elapsed:  0u64,
-                                    unlocked: Uint128::from(2500u128),
-                                    claimed:  Uint128::zero(),
-                                };
-                                QuerierResult::Ok(to_binary(&response))
-                            },
-                            _ => unimplemented!()
-                        }
-                    },
-                    _ => unimplemented!(),
-                }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. })
+                if *contract_addr == HumanAddr::from("token") => {
+                let response = TokenQueryAnswer::Balance { amount: Uint128::from(self.balance) };
+                QuerierResult::Ok(to_binary(&response))
             },
             _ => unimplemented!(),
         }
@@ -49,24 +49,30 @@ impl <K: PartialEq, V> LinearMap<K, V> {
             None
         }
     }
+    pub fn remove (&mut self, key: &K) -> Option<V> {
+        match self.0.iter().position(|(k, _)| key == k) {
+            Some(index) => Some(self.0.remove(index).1),
+            None => None
+        }
+    }
 }
 
-impl <V: Copy> LinearMap<HumanAddr, V> {
+impl <V: Clone> LinearMap<HumanAddr, V> {
     pub fn canonize <A: Api> (&self, api: &A) -> StdResult<LinearMap<CanonicalAddr, V>> {
         let canonized: Result<Vec<_>,_> = self.0.iter().map(
             |(human, value)| match api.canonical_address(human) {
-                Ok(canon) => Ok((canon, *value)),
+                Ok(canon) => Ok((canon, value.clone())),
                 Err(e)    => Err(e)
             }).collect();
         Ok(LinearMap(canonized?))
     }
 }
 
-impl <V: Copy> LinearMap<CanonicalAddr, V> {
+impl <V: Clone> LinearMap<CanonicalAddr, V> {
     pub fn humanize <A: Api> (&self, api: &A) -> StdResult<LinearMap<HumanAddr, V>> {
         let humanized: Result<Vec<_>,_> = self.0.iter().map(
             |(canon, value)| match api.human_address(canon) {
-                Ok(human) => Ok((human, *value)),
+                Ok(human) => Ok((human, value.clone())),
                 Err(e)    => Err(e)
             }).collect();
         Ok(LinearMap(humanized?))
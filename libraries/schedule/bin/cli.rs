@@ -1,5 +1,5 @@
 use serde::Deserialize;
-use sienna_schedule::{*, vesting::*, validate::*};
+use sienna_schedule::{*, validate::*};
 
 use std::{path::Path, fs::read_to_string};
 use serde_json_wasm::from_str;
@@ -12,39 +12,63 @@ fn main () -> Result<(), std::io::Error> {
         .author("Adam A. <adam@hack.bg>")
         .about("Converts a schedule from JSON to Markdown, materializing all portions")
         .arg(Arg::new("PATH")
-            .default_value("../../settings/schedule.json"));
+            .default_value("../../settings/schedule.json"))
+        .arg(Arg::new("check")
+            .long("check")
+            .about("Only validate the schedule, listing every problem found"));
 
     let matches = &app.clone().get_matches();
     match matches.value_of("PATH") {
         Some(path) => {
+            let schedule = get_schedule(path).unwrap();
+            let violations = schedule.violations("");
+            let check = matches.is_present("check");
+            if !violations.is_empty() {
+                eprintln!("{}: {} found {} problem(s):",
+                    if check { "error" } else { "warning" }, path, violations.len());
+                for violation in violations.iter() {
+                    eprintln!("* {}", violation);
+                }
+            }
+            if check {
+                if !violations.is_empty() {
+                    std::process::exit(1)
+                }
+                eprintln!("{} is valid", path);
+                return Ok(())
+            }
             println!("\n# Schedule");
             println!("\n(Generated from schedule.json)\n");
-            let schedule = get_schedule(path).unwrap();
             println!("Internal representation:\n```\n{:#?}\n```", &schedule);
-            schedule.validate().unwrap();
             for pool in schedule.pools.iter() {
                 println!("\n## Pool: *{}*", &pool.name);
                 for account in pool.accounts.iter() {
                     println!("\n### Account: *{}*\n", &account.name);
                     println!("* Amount: **{} attoSIENNA**", &account.amount);
                     println!("* Cliff: **{} attoSIENNA**", &account.cliff);
-                    println!("* Portion size: **{} attoSIENNA**", &account.portion_size());
-                    println!("* Portion count: **{}**\n", &account.portion_count());
-                    let mut portion = if account.cliff > cosmwasm_std::Uint128::zero() { -1 } else { 0 };
-                    let mut balance = 0u128;
+                    if let Some(revoked) = account.revoked {
+                        println!("* Revoked on day {} (releases **{} attoSIENNA**)",
+                            revoked / 86400, account.allocated().unwrap());
+                    }
+                    if account.release == Release::Continuous {
+                        println!("* Released continuously from day {} to day {}",
+                            account.start_at / 86400, account.end() / 86400);
+                        continue
+                    }
+                    if let Release::Milestones(ref milestones) = account.release {
+                        println!("* Milestones: **{}**\n", milestones.len());
+                    } else {
+                        println!("* Portion size: **{} attoSIENNA**", &account.portion_size().unwrap());
+                        println!("* Portion count: **{}**\n", &account.portion_count());
+                    }
                     println!("|portion #|day|unlocked amount (attoSIENNA)|");
                     println!("|:-:|:-:|--:|");
-                    for t in account.start_at..account.end()+1 {
-                        let new_balance = account.unlocked(t, &account.address);
-                        if new_balance != balance {
-                            portion += 1;
-                            balance = new_balance;
-                            println!("|{:>7}|{:>7}|{:>26}|",
-                                if portion == 0 { "cliff".to_string() } else { portion.to_string() },
-                                t / 86400,
-                                balance
-                            );
-                        }
+                    for event in account.timeline().unwrap() {
+                        println!("|{:>7}|{:>7}|{:>26}|",
+                            if event.portion == 0 { "cliff".to_string() } else { event.portion.to_string() },
+                            event.time / 86400,
+                            event.cumulative.u128()
+                        );
                     }
                 }
             }
@@ -36,9 +36,12 @@ impl Humanize<Pool<HumanAddr>> for Pool<CanonicalAddr> {
 impl Humanize<Account<HumanAddr>> for Account<CanonicalAddr> {
     fn humanize <A:Api> (&self, api: &A) -> StdResult<Account<HumanAddr>> {
         let address = api.human_address(&self.address)?;
-        let &Account { amount, cliff, start_at, interval, duration, .. } = self;
-        let name = self.name.clone();
-        Ok(Account { name, amount, cliff, start_at, interval, duration, address })
+        let &Account { shared_address, amount, cliff, start_at, interval, duration, revoked, .. } = self;
+        let name    = self.name.clone();
+        let release = self.release.clone();
+        Ok(Account {
+            name, address, shared_address, amount, cliff, start_at, interval, duration, release, revoked
+        })
     }
 }
 
@@ -57,8 +60,11 @@ impl Canonize<Pool<CanonicalAddr>> for Pool<HumanAddr> {
 impl Canonize<Account<CanonicalAddr>> for Account<HumanAddr> {
     fn canonize <A: Api> (&self, api: &A) -> StdResult<Account<CanonicalAddr>> {
         let address = api.canonical_address(&self.address)?;
-        let &Account { amount, cliff, start_at, interval, duration, .. } = self;
-        let name = self.name.clone();
-        Ok(Account { name, amount, cliff, start_at, interval, duration, address })
+        let &Account { shared_address, amount, cliff, start_at, interval, duration, revoked, .. } = self;
+        let name    = self.name.clone();
+        let release = self.release.clone();
+        Ok(Account {
+            name, address, shared_address, amount, cliff, start_at, interval, duration, release, revoked
+        })
     }
 }
@@ -1,75 +1,322 @@
 //! Error definitions
+//!
+//! Errors are variants of `ScheduleError`, which serializes as `{"<code>":{<fields>}}`
+//! (e.g. `{"pool_full":{"pool":"Investors"}}`). When converted to `StdError` (as happens
+//! when a contract uses `?` on a schedule method), that JSON becomes the message of a
+//! `GenericErr`, so that clients can decode it and switch on the code instead of
+//! matching human-readable strings.
 
-/// Create an error result
-#[macro_export] macro_rules! Error {
-    ($msg:expr) => {
-        Err(cosmwasm_std::StdError::GenericErr {
-            msg: $msg.to_string(),
-            backtrace: None //Some(snafu::Backtrace::generate())
-        })
-    };
+use std::fmt;
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use cosmwasm_std::{StdError, Uint128, to_vec, from_slice};
+use crate::{Seconds, Schedule, Pool, Account};
+
+/// Everything that can be wrong with a `Schedule`, `Pool`, or `Account`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleError {
+    /// Pools don't add up to the schedule's total
+    TotalMismatch     { subtotal: Uint128, total: Uint128 },
+    /// No pool with this name
+    PoolNotFound      { pool: String },
+    /// Accounts don't add up to (or exceed, if partial) the pool's total
+    PoolTotalMismatch { pool: String, subtotal: Uint128, total: Uint128 },
+    /// Pool is not partial, so no accounts can be added to it
+    PoolFull          { pool: String },
+    /// Account is bigger than what's left in a partial pool
+    AccountTooBig     { pool: String, account: String, amount: Uint128, unallocated: Uint128 },
+    /// No account with this name in the pool
+    AccountNotFound   { pool: String, account: String },
+    /// Account has zero amount
+    EmptyAccount      { account: String },
+    /// Account's cliff is bigger than its amount
+    CliffTooBig       { account: String, cliff: Uint128, amount: Uint128 },
+    /// Account's cliff, portions and remainder don't add up to its amount
+    DoesNotAddUp      { account: String },
+    /// Periodic account has a duration but no interval
+    NoInterval        { account: String, duration: Seconds },
+    /// Continuous account has no duration
+    NoDuration        { account: String },
+    /// Milestone account has a cliff
+    MilestonesCliff   { account: String },
+    /// Account's milestones don't add up to its amount
+    MilestonesTotal   { account: String, subtotal: Uint128, amount: Uint128 },
+    /// Account's milestones are not in chronological order
+    MilestonesOrder   { account: String, at: Seconds },
+    /// Account's last milestone is not at the end of its duration
+    MilestonesEnd     { account: String, at: Seconds, duration: Seconds },
+    /// Account has already been revoked
+    AlreadyRevoked    { account: String },
+    /// Account has already unlocked some of its funds
+    AlreadyUnlocked   { account: String },
+    /// Pools add up to more than can be represented
+    TotalOverflow     {},
+    /// Accounts add up to more than can be represented
+    PoolOverflow      { pool: String },
+    /// Account's vesting math overflows
+    AccountOverflow   { account: String },
+    /// More than one pool with this name
+    DuplicatePool     { pool: String },
+    /// More than one account with this name in the pool
+    DuplicateAccount  { pool: String, account: String },
+    /// Account has the same address as another, and not both of them have `shared_address`
+    DuplicateAddress  { pool: String, account: String, other_pool: String, other_account: String },
+    /// No account has this address
+    AddressNotFound   {},
+    /// An account already has the address that another is being moved to
+    AddressInUse      { pool: String, account: String },
+}
+
+impl ScheduleError {
+    /// Recover a `ScheduleError` that was converted into a `StdError`.
+    pub fn from_std (error: &StdError) -> Option<Self> {
+        match error {
+            StdError::GenericErr { msg, .. } => from_slice(msg.as_bytes()).ok(),
+            _ => None
+        }
+    }
+}
+
+impl From<ScheduleError> for StdError {
+    fn from (error: ScheduleError) -> Self {
+        let msg = match to_vec(&error) {
+            Ok(json) => String::from_utf8(json).unwrap_or_else(|_| error.to_string()),
+            Err(_)   => error.to_string()
+        };
+        StdError::GenericErr { msg, backtrace: None }
+    }
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ScheduleError::*;
+        match self {
+            TotalMismatch { subtotal, total } =>
+                write!(f, "schedule: pools add up to {}, expected {}", subtotal, total),
+            PoolNotFound { pool } =>
+                write!(f, "schedule: pool {} not found", pool),
+            PoolTotalMismatch { pool, subtotal, total } =>
+                write!(f, "pool {}: accounts add up to {}, expected {}", pool, subtotal, total),
+            PoolFull { pool } =>
+                write!(f, "pool {}: can't add any more accounts to this pool", pool),
+            AccountTooBig { pool, amount, unallocated, .. } =>
+                write!(f, "pool {}: account ({}) > unallocated funds in pool ({})", pool, amount, unallocated),
+            AccountNotFound { pool, account } =>
+                write!(f, "pool {}: account {} not found", pool, account),
+            EmptyAccount { account } =>
+                write!(f, "account {}: amount must be >0", account),
+            CliffTooBig { account, cliff, amount } =>
+                write!(f, "account {}: cliff ({}) > total ({})", account, cliff, amount),
+            DoesNotAddUp { account } =>
+                write!(f, "account {}: cliff + portions + remainder don't add up to amount", account),
+            NoInterval { account, duration } =>
+                write!(f, "account {}: duration ({}) is set but interval is 0 (use continuous release?)", account, duration),
+            NoDuration { account } =>
+                write!(f, "account {}: continuous release needs duration > 0", account),
+            MilestonesCliff { account } =>
+                write!(f, "account {}: milestone release can't have a cliff", account),
+            MilestonesTotal { account, subtotal, amount } =>
+                write!(f, "account {}: milestones add up to {}, expected {}", account, subtotal, amount),
+            MilestonesOrder { account, at } =>
+                write!(f, "account {}: milestone at {} is not after the previous one", account, at),
+            MilestonesEnd { account, at, duration } =>
+                write!(f, "account {}: last milestone is at {}, expected duration ({})", account, at, duration),
+            AlreadyRevoked { account } =>
+                write!(f, "account {}: already revoked", account),
+            AlreadyUnlocked { account } =>
+                write!(f, "account {}: has already unlocked funds", account),
+            TotalOverflow {} =>
+                write!(f, "schedule: total overflows"),
+            PoolOverflow { pool } =>
+                write!(f, "pool {}: total overflows", pool),
+            AccountOverflow { account } =>
+                write!(f, "account {}: vesting amounts overflow", account),
+            DuplicatePool { pool } =>
+                write!(f, "schedule: more than one pool named {}", pool),
+            DuplicateAccount { pool, account } =>
+                write!(f, "pool {}: more than one account named {}", pool, account),
+            DuplicateAddress { pool, account, other_pool, other_account } =>
+                write!(f, "pool {}: account {} has the same address as account {} in pool {}",
+                    pool, account, other_account, other_pool),
+            AddressNotFound {} =>
+                write!(f, "schedule: no account has this address"),
+            AddressInUse { pool, account } =>
+                write!(f, "pool {}: account {} already has this address", pool, account),
+        }
+    }
 }
 
 /// `impl` error methods on one or more structs
 #[macro_export] macro_rules! define_errors {
     ($(
         $Struct:ident $(<$G:tt$(:$GG:tt)?>)? { $(
+            $(#[$meta:meta])*
             $name:ident
             ($(&$self:ident,)? $($arg:ident : $type:ty),*)
-            { $format:literal $(, $var:expr)* }
+            -> $Variant:ident { $($field:ident $(: $value:expr)?),* $(,)? }
         )* }
     )*) => {
         $( impl $(<$G$(:$GG)?>)? $Struct $(<$G>)? { $(
-            #[doc=$format]
-            pub fn $name<T> ($(&$self,)? $($arg : $type),*) -> cosmwasm_std::StdResult<T> {
-                Error!(format!($format $(, $var)*))
+            $(#[$meta])*
+            pub fn $name<T> ($(&$self,)? $($arg : $type),*) -> Result<T, $crate::ScheduleError> {
+                Err($crate::ScheduleError::$Variant { $($field $(: $value)?),* })
             }
         )* } )*
     }
 }
 
-use crate::{Schedule, Pool, Account};
 define_errors!(
     Schedule<A:Clone> {
-        err_total (&self,) {
-            "schedule: pools add up to {}, expected {}",
-            &self.subtotal(), &self.total
+        /// Pools don't add up to the schedule's total
+        err_total (&self,) -> TotalMismatch {
+            subtotal: self.subtotal().unwrap_or(u128::MAX).into(),
+            total:    self.total
+        }
+        /// Pools add up to more than can be represented
+        err_overflow (&self,) -> TotalOverflow {}
+        /// No pool with this name
+        err_pool_not_found (&self, name: &str) -> PoolNotFound {
+            pool: name.to_string()
+        }
+        /// More than one pool with this name
+        err_duplicate_pool (&self, name: &str) -> DuplicatePool {
+            pool: name.to_string()
+        }
+        /// Two accounts with the same address
+        err_duplicate_address (
+            &self, pool: &str, account: &str, other_pool: &str, other_account: &str
+        ) -> DuplicateAddress {
+            pool:          pool.to_string(),
+            account:       account.to_string(),
+            other_pool:    other_pool.to_string(),
+            other_account: other_account.to_string()
         }
-        err_pool_not_found (&self, name: &str) {
-            "schedule: pool {} not found",
-            &name
+        /// No account with this address
+        err_address_not_found (&self,) -> AddressNotFound {}
+        /// Account already has this address
+        err_address_in_use (&self, pool: &str, account: &str) -> AddressInUse {
+            pool:    pool.to_string(),
+            account: account.to_string()
         }
     }
     Pool<A:Clone> {
-        err_total (&self,) {
-            "pool {}: accounts add up to {}, expected {}",
-            &self.name, &self.subtotal(), &self.total
+        /// Accounts don't add up to the pool's total
+        err_total (&self,) -> PoolTotalMismatch {
+            pool:     self.name.clone(),
+            subtotal: self.subtotal().unwrap_or(u128::MAX).into(),
+            total:    self.total
         }
-        err_pool_full (&self,) {
-            "pool {}: can't add any more accounts to this pool",
-            &self.name
+        /// Accounts add up to more than can be represented
+        err_overflow (&self,) -> PoolOverflow {
+            pool: self.name.clone()
         }
-        err_account_too_big (&self, account: &Account<A>) {
-            "pool {}: account ({}) > unallocated funds in pool ({})",
-            &self.name,
-            account.amount.u128(),
-            self.unallocated()
+        /// Pool is not partial
+        err_pool_full (&self,) -> PoolFull {
+            pool: self.name.clone()
+        }
+        /// Account is bigger than what's left in the pool
+        err_account_too_big (&self, account: &Account<A>) -> AccountTooBig {
+            pool:        self.name.clone(),
+            account:     account.name.clone(),
+            amount:      account.amount,
+            unallocated: self.unallocated().unwrap_or(0).into()
+        }
+        /// No account with this name in the pool
+        err_account_not_found (&self, name: &str) -> AccountNotFound {
+            pool:    self.name.clone(),
+            account: name.to_string()
+        }
+        /// More than one account with this name in the pool
+        err_duplicate_account (&self, name: &str) -> DuplicateAccount {
+            pool:    self.name.clone(),
+            account: name.to_string()
         }
     }
     Account<A:Clone> {
-        err_empty (&self,) {
-            "account {}: amount must be >0",
-            &self.name
+        /// Account has zero amount
+        err_empty (&self,) -> EmptyAccount {
+            account: self.name.clone()
+        }
+        /// Cliff is bigger than amount
+        err_cliff_too_big (&self,) -> CliffTooBig {
+            account: self.name.clone(),
+            cliff:   self.cliff,
+            amount:  self.amount
+        }
+        /// Cliff, portions and remainder don't add up to amount
+        err_does_not_add_up (&self,) -> DoesNotAddUp {
+            account: self.name.clone()
+        }
+        /// Periodic release with duration but no interval
+        err_no_interval (&self,) -> NoInterval {
+            account:  self.name.clone(),
+            duration: self.duration
+        }
+        /// Continuous release without duration
+        err_no_duration (&self,) -> NoDuration {
+            account: self.name.clone()
+        }
+        /// Milestone release with a cliff
+        err_milestones_cliff (&self,) -> MilestonesCliff {
+            account: self.name.clone()
         }
-        err_cliff_too_big (&self,) {
-            "account {}: cliff ({}) > total ({})",
-            &self.name,
-            &self.cliff,
-            &self.amount
+        /// Milestones don't add up to amount
+        err_milestones_total (&self, subtotal: u128) -> MilestonesTotal {
+            account:  self.name.clone(),
+            subtotal: subtotal.into(),
+            amount:   self.amount
         }
-        err_does_not_add_up (&self,) {
-            "account {}: cliff + portions + remainder don't add up to amount",
-            &self.name
+        /// Milestone is not after the previous one
+        err_milestones_order (&self, at: Seconds) -> MilestonesOrder {
+            account: self.name.clone(),
+            at
+        }
+        /// Last milestone is not at `duration`
+        err_milestones_end (&self, at: Seconds) -> MilestonesEnd {
+            account:  self.name.clone(),
+            at,
+            duration: self.duration
+        }
+        /// Vesting math overflows
+        err_overflow (&self,) -> AccountOverflow {
+            account: self.name.clone()
+        }
+        /// Account is already revoked
+        err_already_revoked (&self,) -> AlreadyRevoked {
+            account: self.name.clone()
+        }
+        /// Account has already unlocked funds
+        err_already_unlocked (&self,) -> AlreadyUnlocked {
+            account: self.name.clone()
         }
     }
 );
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+    use cosmwasm_std::{StdError, HumanAddr};
+    use crate::{Pool, Account, ScheduleError};
+    #[test] fn test_error_codes () {
+        let P: Pool<HumanAddr> = Pool::full("P", &[]);
+        let e = P.err_pool_full::<()>().unwrap_err();
+        assert_eq!(e.to_string(),
+                   "pool P: can't add any more accounts to this pool");
+        let std: StdError = e.clone().into();
+        assert_eq!(std, StdError::GenericErr {
+            msg: r#"{"pool_full":{"pool":"P"}}"#.to_string(),
+            backtrace: None
+        });
+        assert_eq!(ScheduleError::from_std(&std),
+                   Some(e));
+        let A = Account::periodic("A", &HumanAddr::from(""), 1, 2, 0, 0, 0);
+        let std: StdError = A.err_cliff_too_big::<()>().unwrap_err().into();
+        assert_eq!(std, StdError::GenericErr {
+            msg: r#"{"cliff_too_big":{"account":"A","cliff":"2","amount":"1"}}"#.to_string(),
+            backtrace: None
+        });
+        assert_eq!(ScheduleError::from_std(&StdError::Unauthorized { backtrace: None }),
+                   None);
+    }
+}
@@ -19,6 +19,7 @@ pub mod errors; pub use errors::*;
 pub mod canon; pub use canon::*;
 pub mod validate;
 pub mod vesting;
+pub mod timeline;
 pub mod mutate;
 
 /// Unit of time
@@ -27,8 +28,11 @@ pub type Seconds = u64;
 /// Unit of account
 pub const ONE_SIENNA: u128 = 1000000000000000000u128;
 
+/// A return type that may contain an error
+pub type Usually<T> = Result<T, ScheduleError>;
+
 /// The most basic return type that may contain an error
-pub type UsuallyOk = StdResult<()>;
+pub type UsuallyOk = Usually<()>;
 
 /// Contains `Pool`s that must add up to `total`.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -40,12 +44,20 @@ pub struct Schedule<A:Clone> {
 impl<A:Clone> Schedule<A> {
     pub fn new (pools: &[Pool<A>]) -> Self {
         let mut s = Schedule { total: Uint128::zero(), pools: pools.to_vec() };
-        s.total = Uint128::from(s.subtotal());
+        // if this overflows, `validate` will say so
+        s.total = Uint128::from(s.subtotal().unwrap_or(u128::MAX));
         s
     }
     /// Sum of all contained pools (expected to equal `self.total`)
-    pub fn subtotal (&self) -> u128 {
-        self.pools.iter().fold(0, |total, pool| total + pool.total.u128())
+    pub fn subtotal (&self) -> Usually<u128> {
+        let mut total = 0u128;
+        for pool in self.pools.iter() {
+            match total.checked_add(pool.total.u128()) {
+                Some(sum) => total = sum,
+                None => return self.err_overflow()
+            }
+        }
+        Ok(total)
     }
 }
 
@@ -68,17 +80,29 @@ impl<A:Clone> Pool<A> {
     }
     pub fn full (name: &str, accounts: &[Account<A>]) -> Self {
         let accounts = accounts.to_vec();
-        let mut total = Uint128::zero();
-        for &Account{amount,..} in accounts.iter() { total += amount }
+        // if this overflows, `validate` will say so
+        let total = accounts.iter()
+            .fold(0u128, |total, account| total.saturating_add(account.amount.u128()))
+            .into();
         Pool { partial: false, name: name.into(), total, accounts }
     }
     /// Sum of all contained accounts - expected to equal total
-    pub fn subtotal (&self) -> u128 {
-        self.accounts.iter().fold(0, |total, acc| total + acc.amount.u128())
+    pub fn subtotal (&self) -> Usually<u128> {
+        let mut total = 0u128;
+        for account in self.accounts.iter() {
+            match total.checked_add(account.allocated()?) {
+                Some(sum) => total = sum,
+                None => return self.err_overflow()
+            }
+        }
+        Ok(total)
     }
     /// Remaining unallocated funds
-    pub fn unallocated (&self) -> u128 {
-        self.total.u128() - self.subtotal()
+    pub fn unallocated (&self) -> Usually<u128> {
+        match self.total.u128().checked_sub(self.subtotal()?) {
+            Some(unallocated) => Ok(unallocated),
+            None => self.err_total()
+        }
     }
 }
 
@@ -94,6 +118,10 @@ pub struct Account<A> {
     pub name:     String,
     /// Recipient address
     pub address:  A,
+    /// Set this on every account that's meant to have the same address as another
+    /// (their unlocked amounts are summed). Otherwise, addresses must be unique.
+    #[serde(default)]
+    pub shared_address: bool,
     /// Funds that this account will release
     pub amount:   Uint128,
     /// If `> 0`, releases this much money the first time, pushing back the regular portions
@@ -104,17 +132,27 @@ pub struct Account<A> {
     pub interval: Seconds,
     /// If `> 0`, vesting stops after this much seconds regardless of how much is left of `total`.
     pub duration: Seconds,
+    /// Whether the amount after the cliff is released in portions or continuously
+    #[serde(default)]
+    pub release:  Release,
+    /// If set, the account was revoked this many seconds after launch
+    /// and nothing more vests after that.
+    #[serde(default)]
+    pub revoked:  Option<Seconds>,
 }
 impl<A:Clone> Account<A> {
     pub fn immediate (name: &str, address: &A, amount: u128) -> Self {
         Self {
             name:     name.into(),
             address:  address.clone(),
+            shared_address: false,
             amount:   amount.into(),
             cliff:    0u128.into(),
             start_at: 0,
             interval: 0,
-            duration: 0
+            duration: 0,
+            release:  Release::Periodic,
+            revoked:  None
         }
     }
     pub fn periodic (
@@ -124,11 +162,79 @@ impl<A:Clone> Account<A> {
         Self {
             name:    name.into(),
             address: address.clone(),
+            shared_address: false,
             amount:  amount.into(),
             cliff:   cliff.into(),
             start_at,
             interval,
-            duration
+            duration,
+            release: Release::Periodic,
+            revoked: None
+        }
+    }
+    pub fn continuous (
+        name: &str, address: &A, amount: u128,
+        cliff: u128, start_at: Seconds, duration: Seconds
+    ) -> Self {
+        Self {
+            name:     name.into(),
+            address:  address.clone(),
+            shared_address: false,
+            amount:   amount.into(),
+            cliff:    cliff.into(),
+            start_at,
+            interval: 0,
+            duration,
+            release:  Release::Continuous,
+            revoked:  None
+        }
+    }
+    pub fn milestones (
+        name: &str, address: &A, start_at: Seconds, milestones: &[(Seconds, u128)]
+    ) -> Self {
+        let milestones: Vec<Milestone> = milestones.iter()
+            .map(|&(at, amount)| Milestone { at, amount: amount.into() })
+            .collect();
+        // if this overflows, `validate` will say so
+        let amount = milestones.iter()
+            .fold(0u128, |total, milestone| total.saturating_add(milestone.amount.u128()))
+            .into();
+        Self {
+            name:     name.into(),
+            address:  address.clone(),
+            shared_address: false,
+            amount,
+            cliff:    0u128.into(),
+            start_at,
+            interval: 0,
+            duration: milestones.last().map(|m| m.at).unwrap_or(0),
+            release:  Release::Milestones(milestones),
+            revoked:  None
         }
     }
 }
+
+/// How an `Account` releases the funds that remain after the cliff.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Release {
+    /// One portion every `interval` seconds, with the remainder added to the last one.
+    #[default]
+    Periodic,
+    /// Every second between `start_at` and `end()` releases a proportional part
+    /// of the amount after the cliff. `interval` is not used.
+    Continuous,
+    /// Arbitrary amounts at arbitrary times. Must add up to `amount`, with the last
+    /// one at `duration`. `cliff` must be 0 and `interval` is not used.
+    Milestones(Vec<Milestone>),
+}
+
+/// A single unlock of an `Account` with `Release::Milestones`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Milestone {
+    /// How many seconds after `start_at` this amount is unlocked
+    pub at:     Seconds,
+    /// How much is unlocked at this point
+    pub amount: Uint128,
+}
@@ -2,30 +2,145 @@
 
 use crate::{*, validate::Validation};
 
-impl<A: Clone> Schedule<A> {
+impl<A: Clone + PartialEq> Schedule<A> {
     pub fn add_account (&mut self, pool_name: &str, account: Account<A>) -> UsuallyOk {
+        let index = match self.pools.iter().position(|pool| pool.name == pool_name) {
+            Some(index) => index,
+            None => return self.err_pool_not_found(pool_name)
+        };
+        self.pools[index].check_account(&account)?;
+        for pool in self.pools.iter() {
+            for other in pool.accounts.iter() {
+                if other.address == account.address && !(other.shared_address && account.shared_address) {
+                    return self.err_duplicate_address(pool_name, &account.name, &pool.name, &other.name)
+                }
+            }
+        }
+        self.pools[index].add_account(account)
+    }
+    pub fn revoke (&mut self, pool_name: &str, account_name: &str, elapsed: Seconds) -> UsuallyOk {
+        for pool in self.pools.iter_mut() {
+            if pool.name == pool_name {
+                return pool.revoke(account_name, elapsed)
+            }
+        }
+        self.err_pool_not_found(pool_name)
+    }
+    /// Remove an account that hasn't unlocked anything yet.
+    /// `elapsed` is `None` if the vesting hasn't begun.
+    pub fn remove_account (
+        &mut self, pool_name: &str, account_name: &str, elapsed: Option<Seconds>
+    ) -> UsuallyOk {
         for pool in self.pools.iter_mut() {
             if pool.name == pool_name {
-                return pool.add_account(account)
+                return pool.remove_account(account_name, elapsed).map(|_| ())
             }
         }
         self.err_pool_not_found(pool_name)
     }
+    /// Replace an account that hasn't unlocked anything yet with `account`,
+    /// which takes its place in the pool. If `account` can't be added,
+    /// the old one stays.
+    pub fn update_account (
+        &mut self, pool_name: &str, account_name: &str, account: Account<A>, elapsed: Option<Seconds>
+    ) -> UsuallyOk {
+        let index = match self.pools.iter().position(|pool| pool.name == pool_name) {
+            Some(index) => index,
+            None => return self.err_pool_not_found(pool_name)
+        };
+        let mut updated = self.clone();
+        let position = updated.pools[index].remove_account(account_name, elapsed)?;
+        updated.add_account(pool_name, account)?;
+        let accounts = &mut updated.pools[index].accounts;
+        if let Some(account) = accounts.pop() {
+            accounts.insert(position, account)
+        }
+        *self = updated;
+        Ok(())
+    }
+    /// Move all accounts of `address` to `new_address`,
+    /// which must not already have any accounts.
+    pub fn change_address (&mut self, address: &A, new_address: &A) -> UsuallyOk {
+        for pool in self.pools.iter() {
+            for account in pool.accounts.iter() {
+                if &account.address == new_address {
+                    return self.err_address_in_use(&pool.name, &account.name)
+                }
+            }
+        }
+        let mut found = false;
+        for pool in self.pools.iter_mut() {
+            for account in pool.accounts.iter_mut() {
+                if &account.address == address {
+                    account.address = new_address.clone();
+                    found = true
+                }
+            }
+        }
+        if !found {
+            return self.err_address_not_found()
+        }
+        Ok(())
+    }
 }
 impl<A: Clone> Pool<A> {
     pub fn add_account (&mut self, account: Account<A>) -> UsuallyOk {
+        self.check_account(&account)?;
+        self.accounts.push(account);
+        if self.unallocated()? == 0 {
+            self.partial = false
+        }
+        self.validate()
+    }
+    /// Whether `account` can be added to this pool
+    pub fn check_account (&self, account: &Account<A>) -> UsuallyOk {
         if !self.partial {
             return self.err_pool_full()
         }
-        if account.amount.u128() > self.unallocated() {
-            return self.err_account_too_big(&account)
+        if account.amount.u128() > self.unallocated()? {
+            return self.err_account_too_big(account)
         }
-        account.validate()?;
-        self.accounts.push(account);
-        if self.unallocated() == 0 {
-            self.partial = false
+        if self.accounts.iter().any(|other| other.name == account.name) {
+            return self.err_duplicate_account(&account.name)
         }
-        self.validate()
+        account.validate()
+    }
+    /// Remove an account that hasn't unlocked anything yet, returning its position.
+    /// Its funds go back to the pool, which becomes partial.
+    /// `elapsed` is `None` if the vesting hasn't begun.
+    pub fn remove_account (&mut self, account_name: &str, elapsed: Option<Seconds>) -> Usually<usize> {
+        let index = match self.accounts.iter().position(|account| account.name == account_name) {
+            Some(index) => index,
+            None => return self.err_account_not_found(account_name)
+        };
+        if let Some(elapsed) = elapsed {
+            let account = &self.accounts[index];
+            if account.vested(elapsed)? > 0 {
+                return account.err_already_unlocked()
+            }
+        }
+        self.accounts.remove(index);
+        if self.unallocated()? > 0 {
+            self.partial = true
+        }
+        Ok(index)
+    }
+    /// Stop the vesting of an account, returning
+    /// whatever hasn't vested yet to the pool.
+    pub fn revoke (&mut self, account_name: &str, elapsed: Seconds) -> UsuallyOk {
+        match self.accounts.iter_mut().find(|account| account.name == account_name) {
+            Some(account) => {
+                if account.revoked.is_some() {
+                    return account.err_already_revoked()
+                }
+                account.revoked = Some(elapsed);
+            },
+            None => return self.err_account_not_found(account_name)
+        }
+        if self.unallocated()? > 0 {
+            self.partial = true
+        }
+        Ok(())
     }
 }
 
@@ -33,7 +148,7 @@ impl<A: Clone> Pool<A> {
 mod tests {
     #![allow(non_snake_case)]
     use cosmwasm_std::HumanAddr;
-    use crate::{Schedule, Pool, Account, validate::Validation};
+    use crate::{Schedule, Pool, Account, ScheduleError, validate::Validation, vesting::Vesting};
     #[test] fn test_add_to_full () {
         let Alice = HumanAddr::from("Alice");
         let Bob   = HumanAddr::from("Bob");
@@ -79,4 +194,142 @@ mod tests {
         assert_eq!(S.add_account("P3", A.clone()),
                    S.err_pool_not_found("P3"));
     }
+    #[test] fn test_revoke () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let mut S = Schedule::new(&[
+            Pool::full("P", &[Account::periodic("A", &Alice, 100, 0, 0, 10, 100)]),
+        ]);
+        assert_eq!(S.revoke("P", "B", 25),
+                   S.pools.get(0).unwrap().err_account_not_found("B"));
+        assert_eq!(S.revoke("Q", "A", 25),
+                   S.err_pool_not_found("Q"));
+        assert_eq!(S.unlocked(25, &Alice), Ok(30));
+        assert_eq!(S.revoke("P", "A", 25),
+                   Ok(()));
+        assert_eq!(S.unlocked(25,   &Alice), Ok(30));
+        assert_eq!(S.unlocked(1000, &Alice), Ok(30));
+        let P = S.pools.get(0).unwrap().clone();
+        assert_eq!(P.partial,       true);
+        assert_eq!(P.subtotal(),    Ok(30));
+        assert_eq!(P.unallocated(), Ok(70));
+        assert_eq!(S.validate(),    Ok(()));
+        let A = P.accounts.get(0).unwrap().clone();
+        assert_eq!(S.revoke("P", "A", 50),
+                   A.err_already_revoked());
+        assert_eq!(S.unlocked(1000, &Alice), Ok(30));
+        assert_eq!(S.add_account("P", Account::immediate("B", &Bob, 70)),
+                   Ok(()));
+        assert_eq!(S.pools.get(0).unwrap().partial, false);
+        assert_eq!(S.unlocked(1000, &Bob), Ok(70));
+    }
+    #[test] fn test_add_duplicate () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let mut S = Schedule::new(&[
+            Pool::partial("P1", 100, &[Account::immediate("A", &Alice, 10)]),
+            Pool::partial("P2", 100, &[]),
+        ]);
+        assert_eq!(S.add_account("P1", Account::immediate("A", &Bob, 10)),
+                   S.pools.get(0).unwrap().err_duplicate_account("A"));
+        assert_eq!(S.add_account("P2", Account::immediate("B", &Alice, 10)),
+                   S.err_duplicate_address("P2", "B", "P1", "A"));
+        let mut B = Account::immediate("B", &Alice, 10);
+        B.shared_address = true;
+        assert_eq!(S.add_account("P2", B.clone()),
+                   S.err_duplicate_address("P2", "B", "P1", "A"));
+        S.pools[0].accounts[0].shared_address = true;
+        assert_eq!(S.add_account("P2", B.clone()),
+                   Ok(()));
+        assert_eq!(S.validate(),
+                   Ok(()));
+    }
+    #[test] fn test_change_address () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let Carol = HumanAddr::from("Carol");
+        let mut A1 = Account::periodic("A1", &Alice, 100, 0, 0, 10, 100);
+        let mut A2 = Account::immediate("A2", &Alice, 50);
+        A1.shared_address = true;
+        A2.shared_address = true;
+        let mut S = Schedule::new(&[
+            Pool::full("P1", &[A1, Account::immediate("B", &Bob, 10)]),
+            Pool::full("P2", &[A2]),
+        ]);
+        assert_eq!(S.change_address(&Carol, &Alice),
+                   S.err_address_in_use("P1", "A1"));
+        assert_eq!(S.change_address(&Alice, &Bob),
+                   S.err_address_in_use("P1", "B"));
+        assert_eq!(S.change_address(&Carol, &HumanAddr::from("Dave")),
+                   S.err_address_not_found());
+        assert_eq!(S.unlocked(25, &Alice), Ok(80));
+        assert_eq!(S.change_address(&Alice, &Carol),
+                   Ok(()));
+        assert_eq!(S.unlocked(25, &Alice), Ok(0));
+        assert_eq!(S.unlocked(25, &Carol), Ok(80));
+        assert_eq!(S.unlocked(25, &Bob),   Ok(10));
+        assert_eq!(S.validate(),           Ok(()));
+    }
+    #[test] fn test_remove_account () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let mut S = Schedule::new(&[
+            Pool::full("P", &[
+                Account::periodic("A", &Alice, 100, 0, 0, 10, 100),
+                Account::periodic("B", &Bob,   100, 0, 50, 10, 100)]),
+        ]);
+        assert_eq!(S.remove_account("Q", "A", None),
+                   S.err_pool_not_found("Q"));
+        assert_eq!(S.remove_account("P", "C", None),
+                   S.pools[0].err_account_not_found("C"));
+        let A = S.pools[0].accounts[0].clone();
+        assert_eq!(S.remove_account("P", "A", Some(25)),
+                   A.err_already_unlocked());
+        assert_eq!(S.remove_account("P", "B", Some(25)),
+                   Ok(()));
+        let P = S.pools[0].clone();
+        assert_eq!(P.accounts,      vec![A]);
+        assert_eq!(P.partial,       true);
+        assert_eq!(P.unallocated(), Ok(100));
+        assert_eq!(S.validate(),    Ok(()));
+        assert_eq!(S.remove_account("P", "A", None),
+                   Ok(()));
+        assert_eq!(S.pools[0].unallocated(), Ok(200));
+    }
+    #[test] fn test_update_account () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let Carol = HumanAddr::from("Carol");
+        let mut S = Schedule::new(&[
+            Pool::full("P", &[
+                Account::immediate("A", &Alice, 100),
+                Account::immediate("B", &Bob,   100)]),
+        ]);
+        let original = S.clone();
+        let too_big = Account::immediate("B", &Carol, 101);
+        assert_eq!(S.update_account("P", "B", too_big, None),
+                   Err(ScheduleError::AccountTooBig {
+                       pool:        "P".to_string(),
+                       account:     "B".to_string(),
+                       amount:      101u128.into(),
+                       unallocated: 100u128.into() }));
+        assert_eq!(S, original);
+        assert_eq!(S.update_account("P", "A", Account::immediate("A", &Bob, 100), None),
+                   S.err_duplicate_address("P", "A", "P", "B"));
+        assert_eq!(S, original);
+        assert_eq!(S.update_account("P", "A", Account::immediate("A", &Carol, 50), None),
+                   Ok(()));
+        let P = S.pools[0].clone();
+        assert_eq!(P.accounts, vec![
+            Account::immediate("A", &Carol, 50),
+            Account::immediate("B", &Bob,   100)]);
+        assert_eq!(P.partial,       true);
+        assert_eq!(P.unallocated(), Ok(50));
+        assert_eq!(S.update_account("P", "A", Account::immediate("A", &Carol, 100), None),
+                   Ok(()));
+        assert_eq!(S.pools[0].partial, false);
+        assert_eq!(S.update_account("P", "A", Account::immediate("A", &Carol, 100), Some(0)),
+                   S.pools[0].accounts[0].err_already_unlocked());
+        assert_eq!(S.validate(), Ok(()));
+    }
 }
@@ -0,0 +1,257 @@
+//! Materialized list of vesting events
+//!
+//! Instead of sampling `unlocked` every second between `start_at` and `end()`,
+//! `timeline` evaluates the vesting math only at the moments when it can change
+//! (start, portions, milestones, revocation, end) and returns the ones where it did.
+//!
+//! Continuous release has no discrete portions: the cliff (if any) is reported at
+//! `start_at`, and everything streamed afterwards is reported as a single event at
+//! `end()` (or at the time of revocation). Use `vested` for the exact amount in between.
+
+use crate::{*, vesting::Vesting};
+
+/// A moment at which an account's unlocked amount increases.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VestingEvent {
+    /// Seconds after launch
+    pub time:       Seconds,
+    /// 0 is the cliff (if there is one), regular portions are counted from 1
+    pub portion:    u64,
+    /// How much is unlocked at this moment
+    pub amount:     Uint128,
+    /// How much is unlocked in total after this moment
+    pub cumulative: Uint128,
+}
+
+/// A `VestingEvent` of a specific account in a `Schedule`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ScheduleEvent<A> {
+    pub pool:    String,
+    pub account: String,
+    pub address: A,
+    pub event:   VestingEvent,
+}
+
+impl<A:Clone> Schedule<A> {
+    /// Events of all accounts, ordered by time (and by position in the schedule
+    /// if they happen at the same time)
+    pub fn timeline (&self) -> Usually<Vec<ScheduleEvent<A>>> {
+        let mut events = vec![];
+        for pool in self.pools.iter() {
+            events.append(&mut pool.timeline()?)
+        }
+        events.sort_by_key(|e| e.event.time);
+        Ok(events)
+    }
+}
+impl<A:Clone+PartialEq> Schedule<A> {
+    /// Events of all accounts with this address, ordered by time
+    pub fn timeline_for (&self, address: &A) -> Usually<Vec<ScheduleEvent<A>>> {
+        Ok(self.timeline()?.into_iter().filter(|e| e.address == *address).collect())
+    }
+    /// First moment after `elapsed` at which the amount unlocked for `address` increases,
+    /// and by how much. While a continuous release is in progress, that's the next second.
+    pub fn next_unlock (&self, elapsed: Seconds, address: &A) -> Usually<Option<(Seconds, u128)>> {
+        let mut next: Option<Seconds> = None;
+        for pool in self.pools.iter() {
+            for account in pool.accounts.iter().filter(|a| a.address == *address) {
+                let streaming = account.release == Release::Continuous && elapsed < Seconds::MAX &&
+                    account.vested(elapsed + 1)? > account.vested(elapsed)?;
+                let time = if streaming {
+                    Some(elapsed + 1)
+                } else {
+                    account.timeline()?.into_iter().map(|e| e.time).find(|&t| t > elapsed)
+                };
+                if let Some(time) = time {
+                    next = Some(next.map_or(time, |next| Seconds::min(next, time)))
+                }
+            }
+        }
+        Ok(match next {
+            Some(time) => {
+                let amount = self.unlocked(time, address)? - self.unlocked(elapsed, address)?;
+                Some((time, amount))
+            },
+            None => None
+        })
+    }
+}
+impl<A:Clone> Pool<A> {
+    /// Events of all accounts in this pool, ordered by time
+    pub fn timeline (&self) -> Usually<Vec<ScheduleEvent<A>>> {
+        let mut events = vec![];
+        for account in self.accounts.iter() {
+            for event in account.timeline()? {
+                events.push(ScheduleEvent {
+                    pool:    self.name.clone(),
+                    account: account.name.clone(),
+                    address: account.address.clone(),
+                    event
+                })
+            }
+        }
+        events.sort_by_key(|e| e.event.time);
+        Ok(events)
+    }
+}
+impl<A:Clone> Account<A> {
+    /// Every moment at which `vested` increases, in chronological order
+    pub fn timeline (&self) -> Usually<Vec<VestingEvent>> {
+        let mut events = vec![];
+        let mut cumulative = 0u128;
+        let mut portion = match self.cliff.u128() { 0 => 1, _ => 0 };
+        for time in self.event_times() {
+            let vested = self.vested(time)?;
+            if vested > cumulative {
+                events.push(VestingEvent {
+                    time,
+                    portion,
+                    amount:     (vested - cumulative).into(),
+                    cumulative: vested.into()
+                });
+                cumulative = vested;
+                portion += 1;
+            }
+        }
+        Ok(events)
+    }
+    /// Sorted list of the moments at which `vested` may change
+    fn event_times (&self) -> Vec<Seconds> {
+        let mut times = vec![self.start_at, self.end()];
+        match self.release {
+            Release::Periodic => if self.interval > 0 {
+                for n in 1..=self.portion_count() {
+                    match n.checked_mul(self.interval).and_then(|t| t.checked_add(self.start_at)) {
+                        Some(t) => times.push(t),
+                        None => break
+                    }
+                }
+            },
+            Release::Continuous => {},
+            Release::Milestones(ref milestones) => for milestone in milestones.iter() {
+                times.push(self.start_at.saturating_add(milestone.at))
+            }
+        }
+        if let Some(revoked) = self.revoked {
+            times.push(revoked)
+        }
+        times.sort();
+        times.dedup();
+        times
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+    use cosmwasm_std::HumanAddr;
+    use crate::{Schedule, Pool, Account, vesting::Vesting, timeline::VestingEvent};
+    fn event (time: u64, portion: u64, amount: u128, cumulative: u128) -> VestingEvent {
+        VestingEvent { time, portion, amount: amount.into(), cumulative: cumulative.into() }
+    }
+    /// Compare against sampling `unlocked` every second
+    fn brute_force (A: &Account<HumanAddr>) -> Vec<(u64, u128)> {
+        let mut events = vec![];
+        let mut balance = 0;
+        for t in 0..A.end()+1 {
+            let unlocked = A.unlocked(t, &A.address).unwrap();
+            if unlocked != balance {
+                events.push((t, unlocked));
+                balance = unlocked;
+            }
+        }
+        events
+    }
+    #[test] fn test_timeline_periodic () {
+        let Alice = HumanAddr::from("Alice");
+        let A = Account::periodic("A", &Alice, 100, 42, 7, 12, 70);
+        assert_eq!(A.timeline(), Ok(vec![
+            event( 7, 0, 42,  42),
+            event(19, 1, 11,  53),
+            event(31, 2, 11,  64),
+            event(43, 3, 11,  75),
+            event(55, 4, 11,  86),
+            event(67, 5, 11,  97),
+            event(77, 6,  3, 100),
+        ]));
+        for A in &[
+            A.clone(),
+            Account::periodic("A", &Alice, 92, 0, 20, 11, 90),
+            Account::periodic("A", &Alice, 100, 40, 10, 0, 30),
+            Account::immediate("A", &Alice, 100),
+            Account::milestones("A", &Alice, 10, &[(0, 10), (5, 20), (50, 70)]),
+        ] {
+            let events: Vec<(u64, u128)> = A.timeline().unwrap().iter()
+                .map(|e| (e.time, e.cumulative.u128())).collect();
+            assert_eq!(events, brute_force(A));
+        }
+    }
+    #[test] fn test_timeline_continuous () {
+        let Alice = HumanAddr::from("Alice");
+        let A = Account::continuous("A", &Alice, 100, 40, 10, 30);
+        assert_eq!(A.timeline(), Ok(vec![
+            event(10, 0, 40,  40),
+            event(40, 1, 60, 100),
+        ]));
+        let mut A = A.clone();
+        A.revoked = Some(25);
+        assert_eq!(A.timeline(), Ok(vec![
+            event(10, 0, 40, 40),
+            event(25, 1, 30, 70),
+        ]));
+    }
+    #[test] fn test_timeline_revoked () {
+        let Alice = HumanAddr::from("Alice");
+        let mut A = Account::periodic("A", &Alice, 100, 0, 0, 10, 100);
+        A.revoked = Some(25);
+        assert_eq!(A.timeline(), Ok(vec![
+            event( 0, 1, 10, 10),
+            event(10, 2, 10, 20),
+            event(20, 3, 10, 30),
+        ]));
+    }
+    #[test] fn test_timeline_schedule () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let S = Schedule::new(&[
+            Pool::full("P1", &[Account::periodic("A", &Alice, 30, 0, 0, 10, 30)]),
+            Pool::full("P2", &[Account::milestones("B", &Bob, 5, &[(0, 1), (5, 2)])]),
+        ]);
+        let events: Vec<(String, u64)> = S.timeline().unwrap().iter()
+            .map(|e| (e.account.clone(), e.event.time)).collect();
+        assert_eq!(events, vec![
+            ("A".to_string(), 0),
+            ("B".to_string(), 5),
+            ("A".to_string(), 10),
+            ("B".to_string(), 10),
+            ("A".to_string(), 20),
+        ]);
+        let events = S.timeline_for(&Bob).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.address == Bob && e.pool == "P2"));
+        assert_eq!(events[1].event, event(10, 2, 2, 3));
+    }
+    #[test] fn test_next_unlock () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let mut A1 = Account::periodic("A1", &Alice, 30, 0, 0, 10, 30);
+        let mut A2 = Account::milestones("A2", &Alice, 5, &[(0, 1), (5, 2)]);
+        A1.shared_address = true;
+        A2.shared_address = true;
+        let S = Schedule::new(&[
+            Pool::full("P1", &[A1]),
+            Pool::full("P2", &[A2, Account::continuous("B", &Bob, 100, 40, 10, 30)]),
+        ]);
+        assert_eq!(S.next_unlock(0,  &Alice), Ok(Some((5, 1))));
+        assert_eq!(S.next_unlock(5,  &Alice), Ok(Some((10, 12))));
+        assert_eq!(S.next_unlock(10, &Alice), Ok(Some((20, 10))));
+        assert_eq!(S.next_unlock(20, &Alice), Ok(None));
+        assert_eq!(S.next_unlock(0,  &Bob),   Ok(Some((10, 40))));
+        assert_eq!(S.next_unlock(10, &Bob),   Ok(Some((11, 2))));
+        assert_eq!(S.next_unlock(39, &Bob),   Ok(Some((40, 2))));
+        assert_eq!(S.next_unlock(40, &Bob),   Ok(None));
+        assert_eq!(S.next_unlock(0,  &HumanAddr::from("Carol")), Ok(None));
+    }
+}
@@ -3,6 +3,10 @@
 //! The `Schedule`, `Pool`, and `Account` structs implement the `Validation` trait, which
 //! provides a `validate` method on top of the implicit schema validation provided by Serde.
 //!
+//! `validate` stops at the first problem. To get all of them at once (e.g. when fixing
+//! a big schedule by hand) use `violations`, which walks the whole tree and reports
+//! where each problem was found, e.g. `pools[2].accounts[17]`.
+//!
 //! Unfortunately, `rustdoc` does not allow for the `impl`s that are defined
 //! in this module to be rendered on this doc page, because they implement
 //! `struct`s defined in another file.
@@ -10,59 +14,191 @@
 //! Documentation of the methods (and errors) defined in this file
 //! can be found in the documentation for those structs.
 
+use std::fmt;
 use crate::*;
 
+/// A problem found during validation, and where in the tree it was found.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Violation {
+    /// e.g. `pools[2].accounts[17]`; empty for the root object
+    pub path:  String,
+    pub error: ScheduleError,
+}
+impl fmt::Display for Violation {
+    fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", &self.error)
+        } else {
+            write!(f, "{}: {}", &self.path, &self.error)
+        }
+    }
+}
+
 /// Trait for something that undergoes validation, returning `Ok` or an error.
 pub trait Validation {
-    /// Default implementation is a no-op
-    fn validate (&self) -> UsuallyOk { Ok(()) }
+    /// Returns the first of the `violations`, if any.
+    fn validate (&self) -> UsuallyOk {
+        match self.violations("").into_iter().next() {
+            Some(Violation { error, .. }) => Err(error),
+            None => Ok(())
+        }
+    }
+    /// Returns all problems found at `path` and below.
+    /// Default implementation finds none.
+    fn violations (&self, _path: &str) -> Vec<Violation> { vec![] }
 }
 impl<A:Validation> Validation for Vec<A> {
-    fn validate (&self) -> UsuallyOk {
-        for item in self.iter() {
-            item.validate()?
+    fn violations (&self, path: &str) -> Vec<Violation> {
+        let mut violations = vec![];
+        for (index, item) in self.iter().enumerate() {
+            violations.append(&mut item.violations(&format!("{}[{}]", path, index)))
         }
-        Ok(())
+        violations
     }
 }
-impl<A:Clone> Validation for Schedule<A> {
-    /// Schedule must contain valid pools that add up to the schedule total
-    fn validate (&self) -> UsuallyOk {
-        self.pools.validate()?;
-        if self.subtotal() != self.total.u128() {
-            return self.err_total()
+impl<A:Clone+PartialEq> Validation for Schedule<A> {
+    /// Schedule must contain valid, uniquely named pools that add up to the schedule total,
+    /// and no address may appear in more than one account unless they all allow it.
+    fn violations (&self, path: &str) -> Vec<Violation> {
+        let pools = field(path, "pools");
+        let mut violations = self.pools.violations(&pools);
+        for (i, pool) in self.pools.iter().enumerate() {
+            if self.pools[..i].iter().any(|other| other.name == pool.name) {
+                report(&mut violations, &format!("{}[{}]", pools, i),
+                    self.err_duplicate_pool(&pool.name))
+            }
+            for (j, account) in pool.accounts.iter().enumerate() {
+                if let Some((other_pool, other_account)) = self.find_address_conflict(i, j) {
+                    report(&mut violations, &format!("{}[{}].accounts[{}]", pools, i, j),
+                        self.err_duplicate_address(
+                            &pool.name, &account.name, &other_pool.name, &other_account.name))
+                }
+            }
+        }
+        match self.subtotal() {
+            Ok(subtotal) => if subtotal != self.total.u128() {
+                report(&mut violations, path, self.err_total())
+            },
+            Err(error) => report(&mut violations, path, Err(error))
         }
-        Ok(())
+        violations
+    }
+}
+impl<A:Clone+PartialEq> Schedule<A> {
+    /// First account before the `account`-th account of the `pool`-th pool
+    /// that has the same address, unless both of them allow sharing it.
+    fn find_address_conflict (&self, pool: usize, account: usize) -> Option<(&Pool<A>, &Account<A>)> {
+        let this = &self.pools[pool].accounts[account];
+        for (i, other_pool) in self.pools.iter().enumerate().take(pool + 1) {
+            let others = if i == pool {
+                &other_pool.accounts[..account]
+            } else {
+                &other_pool.accounts[..]
+            };
+            for other in others.iter() {
+                if other.address == this.address && !(other.shared_address && this.shared_address) {
+                    return Some((other_pool, other))
+                }
+            }
+        }
+        None
     }
 }
 impl<A:Clone> Validation for Pool<A> {
-    fn validate (&self) -> UsuallyOk {
-        self.accounts.validate()?;
-        let invalid_total = if self.partial {
-            self.subtotal() > self.total.u128()
-        } else {
-            self.subtotal() != self.total.u128()
-        };
-        if invalid_total { return self.err_total() }
-        Ok(())
+    fn violations (&self, path: &str) -> Vec<Violation> {
+        let accounts = field(path, "accounts");
+        let mut violations = self.accounts.violations(&accounts);
+        for (i, account) in self.accounts.iter().enumerate() {
+            if self.accounts[..i].iter().any(|other| other.name == account.name) {
+                report(&mut violations, &format!("{}[{}]", accounts, i),
+                    self.err_duplicate_account(&account.name))
+            }
+        }
+        match self.subtotal() {
+            Ok(subtotal) => {
+                let invalid_total = if self.partial {
+                    subtotal > self.total.u128()
+                } else {
+                    subtotal != self.total.u128()
+                };
+                if invalid_total {
+                    report(&mut violations, path, self.err_total())
+                }
+            },
+            Err(error) => report(&mut violations, path, Err(error))
+        }
+        violations
     }
 }
 impl<A:Clone> Validation for Account<A> {
-    fn validate (&self) -> UsuallyOk {
+    fn violations (&self, path: &str) -> Vec<Violation> {
+        let mut violations = vec![];
         if self.amount == Uint128::zero() {
-            return self.err_empty()
+            report(&mut violations, path, self.err_empty())
         }
         if self.cliff > self.amount {
-            return self.err_cliff_too_big()
+            // the remaining checks are meaningless if there's nothing left after the cliff
+            report(&mut violations, path, self.err_cliff_too_big());
+            return violations
+        }
+        let total = self.portion_size().ok()
+            .and_then(|size| size.checked_mul(self.portion_count() as u128))
+            .and_then(|portions| portions.checked_add(self.cliff.u128()))
+            .and_then(|total| total.checked_add(self.remainder().ok()?));
+        if total != Some(self.amount.u128()) {
+            report(&mut violations, path, self.err_does_not_add_up())
         }
-        if self.amount.u128() != (
-            self.cliff.u128() +
-            self.portion_size() * self.portion_count() as u128 +
-            self.remainder()
-        ) {
-            return self.err_does_not_add_up()
+        match self.release {
+            Release::Periodic => if self.interval == 0 && self.duration > 0 {
+                report(&mut violations, path, self.err_no_interval())
+            },
+            Release::Continuous => if self.duration == 0 {
+                report(&mut violations, path, self.err_no_duration())
+            },
+            Release::Milestones(ref milestones) => {
+                if self.cliff > Uint128::zero() {
+                    report(&mut violations, path, self.err_milestones_cliff())
+                }
+                let mut subtotal = Some(0u128);
+                let mut last: Option<Seconds> = None;
+                for milestone in milestones.iter() {
+                    if let Some(previous) = last {
+                        if milestone.at <= previous {
+                            report(&mut violations, path, self.err_milestones_order(milestone.at))
+                        }
+                    }
+                    subtotal = subtotal.and_then(|s| s.checked_add(milestone.amount.u128()));
+                    last = Some(milestone.at);
+                }
+                match subtotal {
+                    Some(subtotal) => if subtotal != self.amount.u128() {
+                        report(&mut violations, path, self.err_milestones_total(subtotal))
+                    },
+                    None => report(&mut violations, path, self.err_overflow())
+                }
+                let last = last.unwrap_or(0);
+                if last != self.duration {
+                    report(&mut violations, path, self.err_milestones_end(last))
+                }
+            }
         }
-        Ok(())
+        violations
+    }
+}
+
+/// Path to a field of the object at `path`
+fn field (path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Add the error (if any) to the list of violations
+fn report (violations: &mut Vec<Violation>, path: &str, result: UsuallyOk) {
+    if let Err(error) = result {
+        violations.push(Violation { path: path.to_string(), error })
     }
 }
 
@@ -70,7 +206,7 @@ impl<A:Clone> Validation for Account<A> {
 mod tests {
     #![allow(non_snake_case)]
     use cosmwasm_std::HumanAddr;
-    use crate::{Schedule, Pool, Account, validate::Validation};
+    use crate::{Schedule, Pool, Account, validate::{Validation, Violation}};
     #[test] fn test_amount_eq_zero () {
         let A = Account::periodic("A", &HumanAddr::from(""), 0, 0, 0, 0, 0);
         assert_eq!(A.validate(),
@@ -93,6 +229,50 @@ mod tests {
         assert_eq!(Schedule::new(&[Pool::partial("P", 1, &[A.clone()])]).validate(),
                    A.err_cliff_too_big());
     }
+    #[test] fn test_duration_without_interval () {
+        let A = Account::periodic("A", &HumanAddr::from(""), 100, 0, 0, 0, 10);
+        assert_eq!(A.validate(),
+                   A.err_no_interval());
+        assert_eq!(Schedule::new(&[Pool::full("P", &[A.clone()])]).validate(),
+                   A.err_no_interval());
+        let A = Account::continuous("A", &HumanAddr::from(""), 100, 0, 0, 10);
+        assert_eq!(A.validate(),
+                   Ok(()));
+    }
+    #[test] fn test_continuous_without_duration () {
+        let A = Account::continuous("A", &HumanAddr::from(""), 100, 0, 0, 0);
+        assert_eq!(A.validate(),
+                   A.err_no_duration());
+        assert_eq!(Schedule::new(&[Pool::full("P", &[A.clone()])]).validate(),
+                   A.err_no_duration());
+    }
+    #[test] fn test_milestones () {
+        let A = Account::milestones("A", &HumanAddr::from(""), 10, &[(0, 10), (5, 20), (50, 70)]);
+        assert_eq!(A.amount.u128(), 100);
+        assert_eq!(A.duration, 50);
+        assert_eq!(A.validate(),
+                   Ok(()));
+        let mut B = A.clone();
+        B.amount = 99u128.into();
+        assert_eq!(B.validate(),
+                   B.err_milestones_total(100));
+        let mut B = A.clone();
+        B.duration = 60;
+        assert_eq!(B.validate(),
+                   B.err_milestones_end(50));
+        let mut B = A.clone();
+        B.cliff = 10u128.into();
+        assert_eq!(B.validate(),
+                   B.err_milestones_cliff());
+        let B = Account::milestones("B", &HumanAddr::from(""), 10, &[(0, 10), (50, 20), (5, 70)]);
+        assert_eq!(B.validate(),
+                   B.err_milestones_order(5));
+        assert_eq!(Schedule::new(&[Pool::full("P", &[A.clone(), B.clone()])]).validate(),
+                   B.err_milestones_order(5));
+        let B = Account::milestones("B", &HumanAddr::from(""), 10, &[(0, 10), (0, 20)]);
+        assert_eq!(B.validate(),
+                   B.err_milestones_order(0));
+    }
     #[test] fn test_account_gt_pool () {
         let A = Account::periodic("A", &HumanAddr::from(""), 2, 0, 0, 0, 0);
         let P = Pool{
@@ -126,4 +306,84 @@ mod tests {
         assert_eq!(S.validate(),
                    S.err_total());
     }
+    #[test] fn test_all_violations () {
+        let A = Account::periodic("A", &HumanAddr::from("A"), 0, 0, 0, 0, 0);
+        let B = Account::periodic("B", &HumanAddr::from("B"), 100, 0, 0, 10, 100);
+        let C = Account::periodic("C", &HumanAddr::from("C"), 1, 2, 0, 0, 0);
+        let D = Account::continuous("D", &HumanAddr::from("D"), 100, 0, 0, 0);
+        let E = Account::periodic("E", &HumanAddr::from("E"), 100, 0, 0, 10, 100);
+        let P1 = Pool::full("P1", &[B.clone(), A.clone()]);
+        let mut P2 = Pool::full("P2", &[C.clone(), E.clone(), D.clone()]);
+        P2.total = 1u128.into();
+        let mut S = Schedule::new(&[P1.clone(), P2.clone()]);
+        S.total = 0u128.into();
+        let violations = S.violations("");
+        assert_eq!(violations, vec![
+            Violation { path: "pools[0].accounts[1]".into(), error: A.err_empty::<()>().unwrap_err() },
+            Violation { path: "pools[1].accounts[0]".into(), error: C.err_cliff_too_big::<()>().unwrap_err() },
+            Violation { path: "pools[1].accounts[2]".into(), error: D.err_no_duration::<()>().unwrap_err() },
+            Violation { path: "pools[1]".into(),             error: P2.err_total::<()>().unwrap_err() },
+            Violation { path: "".into(),                     error: S.err_total::<()>().unwrap_err() },
+        ]);
+        assert_eq!(violations[0].to_string(),
+                   "pools[0].accounts[1]: account A: amount must be >0");
+        assert_eq!(S.validate(),
+                   A.err_empty());
+    }
+    #[test] fn test_duplicates () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let S = Schedule::new(&[
+            Pool::full("P1", &[Account::immediate("A", &Alice, 1), Account::immediate("A", &Bob, 1)]),
+            Pool::full("P2", &[Account::immediate("B", &Bob, 1)]),
+            Pool::full("P1", &[]),
+        ]);
+        assert_eq!(S.violations(""), vec![
+            Violation {
+                path:  "pools[0].accounts[1]".into(),
+                error: S.pools[0].err_duplicate_account::<()>("A").unwrap_err()
+            },
+            Violation {
+                path:  "pools[1].accounts[0]".into(),
+                error: S.err_duplicate_address::<()>("P2", "B", "P1", "A").unwrap_err()
+            },
+            Violation {
+                path:  "pools[2]".into(),
+                error: S.err_duplicate_pool::<()>("P1").unwrap_err()
+            },
+        ]);
+    }
+    #[test] fn test_shared_address () {
+        let Alice = HumanAddr::from("Alice");
+        let mut A1 = Account::immediate("A1", &Alice, 1);
+        let mut A2 = Account::immediate("A2", &Alice, 1);
+        let S = Schedule::new(&[Pool::full("P", &[A1.clone(), A2.clone()])]);
+        assert_eq!(S.validate(),
+                   S.err_duplicate_address("P", "A2", "P", "A1"));
+        A1.shared_address = true;
+        let S = Schedule::new(&[Pool::full("P", &[A1.clone(), A2.clone()])]);
+        assert_eq!(S.validate(),
+                   S.err_duplicate_address("P", "A2", "P", "A1"));
+        A2.shared_address = true;
+        let S = Schedule::new(&[Pool::full("P", &[A1.clone(), A2.clone()])]);
+        assert_eq!(S.validate(),
+                   Ok(()));
+    }
+    #[test] fn test_overflow () {
+        let P = Pool::full("P", &[
+            Account::immediate("A", &HumanAddr::from("A"), u128::MAX),
+            Account::immediate("B", &HumanAddr::from("B"), 1),
+        ]);
+        assert_eq!(P.validate(),
+                   P.err_overflow());
+        let S = Schedule::new(&[
+            Pool::full("P1", &[Account::immediate("A", &HumanAddr::from("A"), u128::MAX)]),
+            Pool::full("P2", &[Account::immediate("B", &HumanAddr::from("B"), 1)]),
+        ]);
+        assert_eq!(S.validate(),
+                   S.err_overflow());
+        let A = Account::milestones("A", &HumanAddr::from("A"), 0, &[(0, u128::MAX), (1, 1)]);
+        assert_eq!(A.validate(),
+                   A.err_overflow());
+    }
 }
@@ -2,78 +2,182 @@
 
 use crate::*;
 
+/// How much of a single account is unlocked (see `Vesting::breakdown`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AccountUnlocked {
+    /// Empty if the breakdown was requested from the account itself
+    pub pool:      String,
+    pub account:   String,
+    /// Amount unlocked at the requested time
+    pub unlocked:  Uint128,
+    /// Amount that the account releases in total
+    pub allocated: Uint128,
+}
+
 pub trait Vesting<A> {
     /// Get total amount unlocked for address `a` at time `t`.
-    fn unlocked (&self, elapsed: Seconds, address: &A) -> u128;
+    fn unlocked (&self, elapsed: Seconds, address: &A) -> Usually<u128>;
+    /// Get amount unlocked for address `a` at time `t` by each account.
+    fn breakdown (&self, elapsed: Seconds, address: &A) -> Usually<Vec<AccountUnlocked>>;
 }
 impl<A:Clone+PartialEq> Vesting<A> for Schedule<A> {
     /// Sum of unlocked amounts for this address for all pools
-    fn unlocked (&self, elapsed: Seconds, address: &A) -> u128 {
-        u128::min(
-            self.pools.iter().fold(0, |total, pool| total + pool.unlocked(elapsed, address)),
-            self.subtotal()
-        )
+    fn unlocked (&self, elapsed: Seconds, address: &A) -> Usually<u128> {
+        let mut unlocked = 0u128;
+        for pool in self.pools.iter() {
+            match unlocked.checked_add(pool.unlocked(elapsed, address)?) {
+                Some(sum) => unlocked = sum,
+                None => return self.err_overflow()
+            }
+        }
+        Ok(u128::min(unlocked, self.subtotal()?))
+    }
+    /// Accounts with this address from all pools, in order
+    fn breakdown (&self, elapsed: Seconds, address: &A) -> Usually<Vec<AccountUnlocked>> {
+        let mut accounts = vec![];
+        for pool in self.pools.iter() {
+            accounts.append(&mut pool.breakdown(elapsed, address)?)
+        }
+        Ok(accounts)
     }
 }
 impl<A:Clone+PartialEq> Vesting<A> for Pool<A> {
     /// Sum of unlocked amounts for this address for all accounts in this pool
-    fn unlocked (&self, elapsed: Seconds, address: &A) -> u128 {
-        u128::min(
-            self.accounts.iter().fold(0, |total, account| total + account.unlocked(elapsed, address)),
-            self.subtotal()
-        )
+    fn unlocked (&self, elapsed: Seconds, address: &A) -> Usually<u128> {
+        let mut unlocked = 0u128;
+        for account in self.accounts.iter() {
+            match unlocked.checked_add(account.unlocked(elapsed, address)?) {
+                Some(sum) => unlocked = sum,
+                None => return self.err_overflow()
+            }
+        }
+        Ok(u128::min(unlocked, self.subtotal()?))
+    }
+    /// Accounts with this address from this pool, in order
+    fn breakdown (&self, elapsed: Seconds, address: &A) -> Usually<Vec<AccountUnlocked>> {
+        let mut accounts = vec![];
+        for account in self.accounts.iter() {
+            for mut unlocked in account.breakdown(elapsed, address)? {
+                unlocked.pool = self.name.clone();
+                accounts.push(unlocked)
+            }
+        }
+        Ok(accounts)
     }
 }
 impl<A:Clone+PartialEq> Vesting<A> for Account<A> {
     /// Unlocked sum for this account at a point in time
-    fn unlocked (&self, elapsed: Seconds, address: &A) -> u128 {
+    fn unlocked (&self, elapsed: Seconds, address: &A) -> Usually<u128> {
         if *address != self.address { // if asking about someone else
-            0
-        } else if elapsed < self.start_at { // if asking about a moment before the start
-            0
-        } else if elapsed >= self.end() { // at the end the full amount must've been vested
-            self.amount.u128()
+            Ok(0)
         } else {
-            u128::min(
-                match self.most_recent_portion(elapsed) {
-                    Some(n) => self.cliff.u128() + (n as u128) * self.portion_size(),
-                    None => 0
-                },
-                self.amount.u128()
-            )
+            self.vested(elapsed)
+        }
+    }
+    /// This account, if it has this address
+    fn breakdown (&self, elapsed: Seconds, address: &A) -> Usually<Vec<AccountUnlocked>> {
+        if *address != self.address {
+            Ok(vec![])
+        } else {
+            Ok(vec![AccountUnlocked {
+                pool:      String::new(),
+                account:   self.name.clone(),
+                unlocked:  self.vested(elapsed)?.into(),
+                allocated: self.allocated()?.into()
+            }])
         }
     }
 }
-impl<A> Account<A> {
+impl<A:Clone> Account<A> {
+    /// Amount unlocked at a point in time, regardless of address.
+    /// Stops growing at the time of revocation.
+    pub fn vested (&self, elapsed: Seconds) -> Usually<u128> {
+        let elapsed = match self.revoked {
+            Some(revoked) => u64::min(elapsed, revoked),
+            None => elapsed
+        };
+        if elapsed < self.start_at { // if asking about a moment before the start
+            return Ok(0)
+        }
+        if elapsed >= self.end() { // at the end the full amount must've been vested
+            return Ok(self.amount.u128())
+        }
+        let vested = match self.release {
+            Release::Periodic => match self.most_recent_portion(elapsed) {
+                Some(n) => (n as u128).checked_mul(self.portion_size()?)
+                    .and_then(|portions| portions.checked_add(self.cliff.u128())),
+                None => Some(0)
+            },
+            Release::Continuous => match self.elapsed(elapsed) {
+                Some(t) => self.cliff.u128().checked_add(self.streamed(t)?),
+                None => Some(0)
+            },
+            Release::Milestones(ref milestones) => match self.elapsed(elapsed) {
+                Some(t) => milestones.iter()
+                    .filter(|m| m.at <= t)
+                    .try_fold(0u128, |total, m| total.checked_add(m.amount.u128())),
+                None => Some(0)
+            }
+        };
+        match vested {
+            Some(vested) => Ok(u128::min(vested, self.amount.u128())),
+            None => self.err_overflow()
+        }
+    }
+    /// Amount that this account releases in total: `amount`,
+    /// or whatever had vested by the time it was revoked.
+    pub fn allocated (&self) -> Usually<u128> {
+        match self.revoked {
+            Some(revoked) => self.vested(revoked),
+            None => Ok(self.amount.u128())
+        }
+    }
     /// Size of regular (non-cliff) portions.
-    pub fn portion_size (&self) -> u128 {
+    pub fn portion_size (&self) -> Usually<u128> {
         if self.portion_count() > 0 {
-            self.amount_after_cliff() / self.portion_count() as u128
+            Ok(self.amount_after_cliff()? / self.portion_count() as u128)
         } else {
-            0
+            Ok(0)
         }
     }
     /// Amount to vest after the cliff
-    pub fn amount_after_cliff (&self) -> u128 {
-        assert!(self.amount >= self.cliff);
-        self.amount.u128() - self.cliff.u128()
+    pub fn amount_after_cliff (&self) -> Usually<u128> {
+        match self.amount.u128().checked_sub(self.cliff.u128()) {
+            Some(amount) => Ok(amount),
+            None => self.err_cliff_too_big()
+        }
     }
     /// Number of non-cliff portions.
     pub fn portion_count (&self) -> u64 {
-        if self.interval > 0 {
-            (self.duration / self.interval) as u64
-        } else {
-            0
-        }
+        self.duration.checked_div(self.interval).unwrap_or(0)
     }
     /// If `(amount-cliff)` doesn't divide evenly by `portion_size`,
     /// the remainder is added to the last portion.
-    pub fn remainder (&self) -> u128 {
-        self.amount_after_cliff() - self.portion_size() * self.portion_count() as u128
+    pub fn remainder (&self) -> Usually<u128> {
+        match self.portion_size()?.checked_mul(self.portion_count() as u128)
+            .and_then(|portions| self.amount_after_cliff().ok()?.checked_sub(portions))
+        {
+            Some(remainder) => Ok(remainder),
+            None => self.err_overflow()
+        }
+    }
+    /// Part of the amount after the cliff released `t` seconds after start
+    /// (continuous release only). Computed as `amount * t / duration`, but split
+    /// into quotient and remainder so that the intermediate product can't overflow.
+    pub fn streamed (&self, t: Seconds) -> Usually<u128> {
+        let amount = self.amount_after_cliff()?;
+        if self.duration > 0 {
+            let t        = u128::from(u64::min(t, self.duration));
+            let duration = u128::from(self.duration);
+            Ok(amount / duration * t + amount % duration * t / duration)
+        } else {
+            Ok(amount)
+        }
     }
     /// Timestamp of last vesting (when remainder is received)
     pub fn end (&self) -> Seconds {
-        self.start_at + self.duration
+        self.start_at.saturating_add(self.duration)
     }
     /// Time elapsed since start
     pub fn elapsed (&self, t: Seconds) -> Option<Seconds> {
@@ -84,16 +188,23 @@ impl<A> Account<A> {
         }
     }
     /// Most recent portion vested at time `t`
+    /// (always 0 if there are no regular portions, i.e. `interval == 0`)
     pub fn most_recent_portion (&self, t: Seconds) -> Option<u64> {
-        self.elapsed(t).map(|elapsed| u64::min(
-            elapsed / self.interval + match self.cliff.u128() { 0 => 1, _ => 0 },
-            self.portion_count()
+        self.elapsed(t).map(|elapsed| elapsed.checked_div(self.interval).map_or(0, |portions|
+            u64::min(
+                portions + match self.cliff.u128() { 0 => 1, _ => 0 },
+                self.portion_count()
+            )
         ))
     }
     /// Whether a portion is unlocked at the exact moment specified
+    /// (only the start, if there are no regular portions)
     pub fn vests_at (&self, t: Seconds) -> bool {
         match self.elapsed(t) {
-            Some(elapsed) => elapsed % self.interval == 0,
+            Some(elapsed) => match elapsed.checked_rem(self.interval) {
+                Some(remainder) => remainder == 0,
+                None => elapsed == 0
+            },
             None => false
         }
     }
@@ -103,7 +214,7 @@ impl<A> Account<A> {
 mod tests {
     #![allow(non_snake_case)]
     use cosmwasm_std::HumanAddr;
-    use crate::{Schedule, Pool, Account, vesting::Vesting};
+    use crate::{Schedule, Pool, Account, vesting::{Vesting, AccountUnlocked}};
     #[test] fn blank () {
         // some imaginary people:
         let Alice = HumanAddr::from("Alice");
@@ -115,9 +226,9 @@ mod tests {
             Schedule::new(&[ Pool::full("", &[ Account::immediate("", &Alice, 0) ]) ]),
             Schedule::new(&[ Pool::partial("", 1, &[ Account::immediate("", &Alice, 0) ]) ])
         ] {
-          assert_eq!(S.unlocked(0, &Alice), 0);
-          assert_eq!(S.unlocked(1, &Alice), 0);
-          assert_eq!(S.unlocked(1001, &Bob), 0);
+          assert_eq!(S.unlocked(0, &Alice).unwrap(), 0);
+          assert_eq!(S.unlocked(1, &Alice).unwrap(), 0);
+          assert_eq!(S.unlocked(1001, &Bob).unwrap(), 0);
         }
     }
     #[test] fn vest_immediate () {
@@ -128,23 +239,23 @@ mod tests {
         let P = Pool::full("", &[A.clone()]);
         let S = Schedule::new(&[P.clone()]);
         for (l, r) in &[
-            (A.amount.u128(),        100),
-            (A.cliff.u128(),           0),
-            (A.amount_after_cliff(), 100),
-            (A.start_at.into(),        0),
-            (A.interval.into(),        0),
-            (A.duration.into(),        0),
-            (A.portion_count().into(), 0),
-            (A.portion_size(),         0),
-            (A.remainder(),          100),
+            (A.amount.u128(),                 100),
+            (A.cliff.u128(),                    0),
+            (A.amount_after_cliff().unwrap(), 100),
+            (A.start_at.into(),                 0),
+            (A.interval.into(),                 0),
+            (A.duration.into(),                 0),
+            (A.portion_count().into(),          0),
+            (A.portion_size().unwrap(),         0),
+            (A.remainder().unwrap(),          100),
         ] {
             assert_eq!(l, r);
         }
         assert_eq!(100, P.total.u128());
         assert_eq!(100, S.total.u128());
         for t in 0..100 {
-            assert_eq!(100, S.unlocked(t, &Alice));
-            assert_eq!(  0, S.unlocked(t, &Bob));
+            assert_eq!(100, S.unlocked(t, &Alice).unwrap());
+            assert_eq!(  0, S.unlocked(t, &Bob).unwrap());
         }
     }
     #[test] fn vest_immediate_as_cliff () { // different way of expressing the same thing
@@ -155,23 +266,23 @@ mod tests {
         let P = Pool::full("", &[A.clone()]);
         let S = Schedule::new(&[P.clone()]);
         for (l, r) in &[
-            (A.amount.u128(),        100),
-            (A.cliff.u128(),         100),
-            (A.amount_after_cliff(),   0),
-            (A.start_at.into(),        0),
-            (A.interval.into(),        0),
-            (A.duration.into(),        0),
-            (A.portion_count().into(), 0),
-            (A.portion_size(),         0),
-            (A.remainder(),            0),
+            (A.amount.u128(),               100),
+            (A.cliff.u128(),                100),
+            (A.amount_after_cliff().unwrap(), 0),
+            (A.start_at.into(),               0),
+            (A.interval.into(),               0),
+            (A.duration.into(),               0),
+            (A.portion_count().into(),        0),
+            (A.portion_size().unwrap(),       0),
+            (A.remainder().unwrap(),          0),
         ] {
             assert_eq!(l, r);
         }
         assert_eq!(100, P.total.u128());
         assert_eq!(100, S.total.u128());
         for t in 0..100 {
-            assert_eq!(100, S.unlocked(t, &Alice));
-            assert_eq!(  0, S.unlocked(t, &Bob));
+            assert_eq!(100, S.unlocked(t, &Alice).unwrap());
+            assert_eq!(  0, S.unlocked(t, &Bob).unwrap());
         }
     }
     #[test] fn vest_periodic_with_cliff () {
@@ -183,16 +294,16 @@ mod tests {
         assert_eq!(100, S.total.u128());
         assert_eq!(100, P.total.u128());
         for (l, r) in &[
-            (A.amount.u128(),        100),
-            (A.cliff.u128(),          42),
-            (A.amount_after_cliff(),  58),
-            (A.start_at.into(),        7),
-            (A.interval.into(),       12),
-            (A.duration.into(),       70),
-            (A.end().into(),          77),
-            (A.portion_count().into(), 5),
-            (A.portion_size(),        11),
-            (A.remainder(),            3),
+            (A.amount.u128(),                100),
+            (A.cliff.u128(),                  42),
+            (A.amount_after_cliff().unwrap(), 58),
+            (A.start_at.into(),                7),
+            (A.interval.into(),               12),
+            (A.duration.into(),               70),
+            (A.end().into(),                  77),
+            (A.portion_count().into(),         5),
+            (A.portion_size().unwrap(),       11),
+            (A.remainder().unwrap(),           3),
         ] {
             assert_eq!(l, r);
         }
@@ -202,8 +313,8 @@ mod tests {
         let mut b = 0;
         for t in 1..200 {
             if t == A.start_at + A.duration + A.interval { break }
-            a = S.unlocked(t, &Alice);
-            b = S.unlocked(t, &Bob);
+            a = S.unlocked(t, &Alice).unwrap();
+            b = S.unlocked(t, &Bob).unwrap();
             print!("{:>12}│", t);
             println!("{:>12}│{:>12}│{:>12}│", if t < A.start_at {
                 assert_eq!(a, 0);
@@ -213,7 +324,7 @@ mod tests {
                 assert_eq!(a, A.cliff.u128());
                 assert_eq!(b, 0);
                 String::from("🚀 cliff")
-            } else if t == A.end() && A.remainder() > 0 {
+            } else if t == A.end() && A.remainder().unwrap() > 0 {
                 assert_eq!(a, A.amount.u128());
                 assert_eq!(b, 0);
                 String::from("✨ remainder")
@@ -223,7 +334,7 @@ mod tests {
                 String::from("✅ done")
             } else if A.vests_at(t) {
                 let p = A.most_recent_portion(t).unwrap() as u128;
-                assert_eq!(a, A.cliff.u128() + p * A.portion_size());
+                assert_eq!(a, A.cliff.u128() + p * A.portion_size().unwrap());
                 assert_eq!(b, 0);
                 format!("💸 vest #{}", p)
             } else {
@@ -242,16 +353,16 @@ mod tests {
         assert_eq!(92, S.total.u128());
         assert_eq!(92, P.total.u128());
         for (l, r) in &[
-            (A.amount.u128(),         92),
-            (A.cliff.u128(),           0),
-            (A.amount_after_cliff(),  92),
-            (A.start_at.into(),       20),
-            (A.interval.into(),       11),
-            (A.duration.into(),       90),
-            (A.end().into(),         110),
-            (A.portion_count().into(), 8),
-            (A.portion_size(),        11),
-            (A.remainder(),            4),
+            (A.amount.u128(),                 92),
+            (A.cliff.u128(),                   0),
+            (A.amount_after_cliff().unwrap(), 92),
+            (A.start_at.into(),               20),
+            (A.interval.into(),               11),
+            (A.duration.into(),               90),
+            (A.end().into(),                 110),
+            (A.portion_count().into(),         8),
+            (A.portion_size().unwrap(),       11),
+            (A.remainder().unwrap(),           4),
         ] {
             assert_eq!(l, r);
         }
@@ -261,14 +372,14 @@ mod tests {
         let mut b = 0;
         for t in 1..200 {
             if t == A.start_at + A.duration + A.interval { break }
-            a = S.unlocked(t, &Alice);
-            b = S.unlocked(t, &Bob);
+            a = S.unlocked(t, &Alice).unwrap();
+            b = S.unlocked(t, &Bob).unwrap();
             print!("{:>12}│", t);
             println!("{:>12}│{:>12}│{:>12}│", if t < A.start_at {
                 assert_eq!(a, 0);
                 assert_eq!(b, 0);
                 String::from("😴 pre")
-            } else if t == A.end() && A.remainder() > 0 {
+            } else if t == A.end() && A.remainder().unwrap() > 0 {
                 assert_eq!(a, A.amount.u128());
                 assert_eq!(b, 0);
                 String::from("✨ remainder")
@@ -278,7 +389,7 @@ mod tests {
                 String::from("✅ done")
             } else if A.vests_at(t) {
                 let p = A.most_recent_portion(t).unwrap() as u128;
-                assert_eq!(a, p * A.portion_size());
+                assert_eq!(a, p * A.portion_size().unwrap());
                 assert_eq!(b, 0);
                 format!("💸 vest #{}", p)
             } else {
@@ -288,4 +399,165 @@ mod tests {
         assert_eq!(a, A.amount.u128());
         assert_eq!(b, 0);
     }
+    #[test] fn vest_continuous_with_cliff () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob = HumanAddr::from("Bob");
+        let A = Account::continuous("", &Alice, 100, 40, 10, 30);
+        let P = Pool::full("", &[A.clone()]);
+        let S = Schedule::new(&[P.clone()]);
+        assert_eq!(100, S.total.u128());
+        assert_eq!(100, P.total.u128());
+        for (l, r) in &[
+            (A.amount.u128(),                100),
+            (A.cliff.u128(),                  40),
+            (A.amount_after_cliff().unwrap(), 60),
+            (A.start_at.into(),               10),
+            (A.duration.into(),               30),
+            (A.end().into(),                  40),
+        ] {
+            assert_eq!(l, r);
+        }
+        println!("\n {:<11}│ {:<11} │ {:<11}│ {:<11}", "T", "Event", "Alice", "Bob");
+        println!("{:─^52}┐", "");
+        let mut a = 0;
+        let mut b = 0;
+        for t in 1..50 {
+            a = S.unlocked(t, &Alice).unwrap();
+            b = S.unlocked(t, &Bob).unwrap();
+            print!("{:>12}│", t);
+            println!("{:>12}│{:>12}│{:>12}│", if t < A.start_at {
+                assert_eq!(a, 0);
+                assert_eq!(b, 0);
+                String::from("😴 pre")
+            } else if t == A.start_at {
+                assert_eq!(a, A.cliff.u128());
+                assert_eq!(b, 0);
+                String::from("🚀 cliff")
+            } else if t >= A.end() {
+                assert_eq!(a, A.amount.u128());
+                assert_eq!(b, 0);
+                String::from("✅ done")
+            } else {
+                assert_eq!(a, A.cliff.u128() + 2 * (t - A.start_at) as u128);
+                assert_eq!(b, 0);
+                String::from("🌊 stream")
+            }, &a, &b);
+        }
+        assert_eq!(a, A.amount.u128());
+        assert_eq!(b, 0);
+    }
+    #[test] fn vest_continuous_no_cliff () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob = HumanAddr::from("Bob");
+        let A = Account::continuous("", &Alice, 92, 0, 20, 90);
+        let P = Pool::full("", &[A.clone()]);
+        let S = Schedule::new(&[P.clone()]);
+        assert_eq!(92, S.total.u128());
+        assert_eq!(92, P.total.u128());
+        assert_eq!(110, A.end());
+        let mut previous = 0;
+        for t in 0..200 {
+            let a = S.unlocked(t, &Alice).unwrap();
+            assert_eq!(S.unlocked(t, &Bob).unwrap(), 0);
+            if t <= A.start_at {
+                assert_eq!(a, 0);
+            } else if t >= A.end() {
+                assert_eq!(a, A.amount.u128());
+            } else {
+                // rounds down, so the whole amount is only reached at the end
+                assert_eq!(a, 92 * (t - A.start_at) as u128 / 90);
+                assert!(a < A.amount.u128());
+            }
+            assert!(a >= previous, "unlocked amount must never decrease");
+            previous = a;
+        }
+    }
+    #[test] fn vest_milestones () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob = HumanAddr::from("Bob");
+        let A = Account::milestones("", &Alice, 10, &[(0, 10), (5, 20), (50, 70)]);
+        let P = Pool::full("", &[A.clone()]);
+        let S = Schedule::new(&[P.clone()]);
+        assert_eq!(100, S.total.u128());
+        assert_eq!(60, A.end());
+        for t in 0..100 {
+            assert_eq!(S.unlocked(t, &Bob).unwrap(), 0);
+            assert_eq!(S.unlocked(t, &Alice).unwrap(), match t {
+                0..=9   => 0,
+                10..=14 => 10,
+                15..=59 => 30,
+                _       => 100
+            }, "t={}", t);
+        }
+    }
+    #[test] fn vest_no_interval () {
+        // cliff and nothing else until the end; used to divide by zero
+        let Alice = HumanAddr::from("Alice");
+        let A = Account::periodic("", &Alice, 100, 40, 10, 0, 30);
+        assert_eq!(A.portion_count(), 0);
+        assert_eq!(A.most_recent_portion(5), None);
+        assert_eq!(A.most_recent_portion(20), Some(0));
+        assert_eq!(A.vests_at(10), true);
+        assert_eq!(A.vests_at(20), false);
+        assert_eq!(A.vested(9),  Ok(0));
+        assert_eq!(A.vested(10), Ok(40));
+        assert_eq!(A.vested(39), Ok(40));
+        assert_eq!(A.vested(40), Ok(100));
+    }
+    #[test] fn vest_cliff_gt_amount () {
+        // invalid, but must return an error instead of panicking
+        let Alice = HumanAddr::from("Alice");
+        let A = Account::periodic("", &Alice, 100, 200, 0, 10, 100);
+        assert_eq!(A.amount_after_cliff(), A.err_cliff_too_big());
+        assert_eq!(A.portion_size(),       A.err_cliff_too_big());
+        assert_eq!(A.remainder(),          A.err_cliff_too_big());
+        assert_eq!(A.unlocked(5, &Alice),  A.err_cliff_too_big());
+        let A = Account::continuous("", &Alice, 100, 200, 0, 100);
+        assert_eq!(A.unlocked(5, &Alice),  A.err_cliff_too_big());
+    }
+    #[test] fn vest_overflow () {
+        let Alice = HumanAddr::from("Alice");
+        // amounts near the limit don't overflow the intermediate products
+        let A = Account::continuous("", &Alice, u128::MAX, 0, 0, u64::MAX);
+        assert_eq!(A.unlocked(u64::MAX - 1, &Alice), Ok(u128::MAX - u128::MAX / u128::from(u64::MAX)));
+        assert_eq!(A.unlocked(u64::MAX, &Alice), Ok(u128::MAX));
+        let A = Account::periodic("", &Alice, u128::MAX, 1, u64::MAX, u64::MAX, u64::MAX);
+        assert_eq!(A.end(), u64::MAX);
+        assert_eq!(A.unlocked(u64::MAX, &Alice), Ok(u128::MAX));
+        // pools and schedules that add up to too much return errors
+        let P = Pool::full("P", &[
+            Account::immediate("A", &Alice, u128::MAX),
+            Account::immediate("B", &Alice, 1)
+        ]);
+        assert_eq!(P.unlocked(0, &Alice), P.err_overflow());
+        let S = Schedule::new(&[
+            Pool::full("P1", &[Account::immediate("A", &Alice, u128::MAX)]),
+            Pool::full("P2", &[Account::immediate("B", &Alice, 1)])
+        ]);
+        assert_eq!(S.unlocked(0, &Alice), S.err_overflow());
+    }
+    #[test] fn vest_breakdown () {
+        let Alice = HumanAddr::from("Alice");
+        let Bob   = HumanAddr::from("Bob");
+        let mut A1 = Account::periodic("A1", &Alice, 100, 0, 0, 10, 100);
+        let mut A2 = Account::immediate("A2", &Alice, 50);
+        A1.shared_address = true;
+        A2.shared_address = true;
+        let S = Schedule::new(&[
+            Pool::full("Investors", &[A1, Account::immediate("B", &Bob, 1)]),
+            Pool::full("Advisors",  &[A2]),
+        ]);
+        assert_eq!(S.breakdown(25, &Alice), Ok(vec![
+            AccountUnlocked {
+                pool: "Investors".into(), account: "A1".into(),
+                unlocked: 30u128.into(), allocated: 100u128.into()
+            },
+            AccountUnlocked {
+                pool: "Advisors".into(), account: "A2".into(),
+                unlocked: 50u128.into(), allocated: 50u128.into()
+            },
+        ]));
+        assert_eq!(S.unlocked(25, &Alice), Ok(80));
+        assert_eq!(S.breakdown(25, &HumanAddr::from("Carol")), Ok(vec![]));
+    }
 }